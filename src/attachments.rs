@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use reqwest::{
+    multipart::{Form, Part},
+    Url,
+};
+use serde::Serialize;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    case_details::{Attachment, CaseDetails},
+    send_command, FogbugzApi, ResponseError,
+};
+
+/// File sent along with a case command, e.g. a crash dump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileUpload {
+    pub file_name: String,
+    pub content: Vec<u8>,
+    pub mime_type: Option<String>,
+}
+
+impl FileUpload {
+    pub fn new(file_name: impl AsRef<str>, content: impl Into<Vec<u8>>) -> Self {
+        Self {
+            file_name: file_name.as_ref().to_string(),
+            content: content.into(),
+            mime_type: None,
+        }
+    }
+    pub fn mime_type(mut self, mime_type: impl AsRef<str>) -> Self {
+        self.mime_type = Some(mime_type.as_ref().to_string());
+        self
+    }
+    /// Reads the file into memory, keeping its file name.
+    pub async fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let content = tokio::fs::read(path).await?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self::new(file_name, content))
+    }
+}
+
+/// Multipart body for a command with files, the request fields become text
+/// parts and the files are sent as `File1`, `File2`, ... with `nFileCount`.
+pub(crate) fn multipart_form<T: Serialize>(
+    request: &T,
+    files: &[FileUpload],
+) -> Result<Form, ResponseError> {
+    let mut form = Form::new();
+    if let serde_json::Value::Object(fields) = serde_json::to_value(request)? {
+        for (name, value) in fields {
+            if let Some(text) = form_text(value) {
+                form = form.text(name, text);
+            }
+        }
+    }
+    form = form.text("nFileCount", files.len().to_string());
+    for (i, file) in files.iter().enumerate() {
+        let mut part = Part::bytes(file.content.clone()).file_name(file.file_name.clone());
+        if let Some(mime_type) = &file.mime_type {
+            part = part.mime_str(mime_type)?;
+        }
+        form = form.part(format!("File{}", i + 1), part);
+    }
+    Ok(form)
+}
+
+/// Sends a command that may carry files, as multipart when there are any and
+/// as JSON otherwise, returning the response body.
+pub(crate) async fn send_with_files<T: Serialize>(
+    api: &FogbugzApi,
+    command: &str,
+    request: &T,
+    files: &[FileUpload],
+) -> Result<serde_json::Value, ResponseError> {
+    if files.is_empty() {
+        return send_command(api, command, request).await;
+    }
+    let url = Url::parse(&api.url)?.join(command)?;
+    #[cfg(feature = "leaky-bucket")]
+    api.limiter.acquire_one().await;
+    let response = api
+        .client
+        .post(url)
+        .bearer_auth(&api.api_key)
+        .multipart(multipart_form(request, files)?)
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        let json: serde_json::Value = response.json().await?;
+        Ok(json)
+    } else {
+        let json: serde_json::Value = response.json().await?;
+        Err(ResponseError::FogbugzError(json))
+    }
+}
+
+/// Like [`send_with_files`] for the commands answering with the changed case.
+pub(crate) async fn send_case_command<T: Serialize>(
+    api: &FogbugzApi,
+    command: &str,
+    request: &T,
+    files: &[FileUpload],
+) -> Result<CaseDetails, ResponseError> {
+    let mut json = send_with_files(api, command, request, files).await?;
+    let mut case_details = CaseDetails::from_value(json["data"]["case"].take())?;
+    api.resolve_statuses(&mut case_details).await;
+    Ok(case_details)
+}
+
+fn form_text(value: serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Array(values) => Some(
+            values
+                .into_iter()
+                .filter_map(form_text)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        value => Some(value.to_string()),
+    }
+}
+
+impl Attachment {
+    /// Streams the attachment into `writer`, returning the number of bytes written.
+    pub async fn download<W>(&self, api: &FogbugzApi, writer: &mut W) -> Result<u64, ResponseError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut url = Url::parse(&api.url)?.join(&self.url.replace("&amp;", "&"))?;
+        url.query_pairs_mut().append_pair("token", &api.api_key);
+        #[cfg(feature = "leaky-bucket")]
+        api.limiter.acquire_one().await;
+        let mut response = api.client.get(url).bearer_auth(&api.api_key).send().await?;
+
+        if response.status().is_success() {
+            let mut written = 0;
+            while let Some(chunk) = response.chunk().await? {
+                writer.write_all(&chunk).await?;
+                written += chunk.len() as u64;
+            }
+            writer.flush().await?;
+            Ok(written)
+        } else {
+            let text = response.text().await?;
+            let json = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+            Err(ResponseError::FogbugzError(json))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form_text() {
+        assert_eq!(form_text(serde_json::json!(null)), None);
+        assert_eq!(form_text(serde_json::json!("a")), Some("a".to_string()));
+        assert_eq!(form_text(serde_json::json!(42)), Some("42".to_string()));
+        assert_eq!(
+            form_text(serde_json::json!(["ixBug", "sTitle"])),
+            Some("ixBug,sTitle".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_upload_from_path() {
+        let path = std::env::temp_dir().join("fogbugz-rs-upload.dmp");
+        tokio::fs::write(&path, b"crash").await.unwrap();
+        let upload = FileUpload::from_path(&path).await.unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+        assert_eq!(upload, FileUpload::new("fogbugz-rs-upload.dmp", *b"crash"));
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    metadata::{self, Area, CategoryInfo, Milestone, Person, PriorityInfo, Project, StatusInfo},
+    FogbugzApi, ResponseError,
+};
+
+/// Default time after which cached metadata is fetched again.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Cache of the metadata lists (projects, people, statuses, ...) of an
+/// instance. Shared by all clones of a [`FogbugzApi`].
+#[derive(Debug)]
+pub struct MetadataCache {
+    ttl: Duration,
+    records: RwLock<sealed::Records>,
+}
+
+impl Default for MetadataCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+/// Metadata lists as plain JSON, used to pre-seed a [`MetadataCache`].
+/// Lists that are missing are fetched from the server on first use.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataSnapshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<Project>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub areas: Option<Vec<Area>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub people: Option<Vec<Person>>,
+    #[serde(rename = "fixfors", skip_serializing_if = "Option::is_none")]
+    pub milestones: Option<Vec<Milestone>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statuses: Option<Vec<StatusInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<CategoryInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priorities: Option<Vec<PriorityInfo>>,
+}
+
+impl MetadataCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            records: RwLock::default(),
+        }
+    }
+    /// Creates a cache pre-seeded with the lists of `snapshot`.
+    pub fn from_snapshot(ttl: Duration, snapshot: MetadataSnapshot) -> Self {
+        let cache = Self::new(ttl);
+        cache.seed(snapshot);
+        cache
+    }
+    /// Creates a cache pre-seeded from a JSON [`MetadataSnapshot`].
+    pub fn from_json(ttl: Duration, json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self::from_snapshot(ttl, serde_json::from_str(json)?))
+    }
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+    /// Stores the lists of `snapshot`, replacing the cached ones.
+    pub fn seed(&self, snapshot: MetadataSnapshot) {
+        fn put<T: MetadataRecord>(cache: &MetadataCache, records: Option<Vec<T>>) {
+            if let Some(records) = records {
+                cache.insert(records);
+            }
+        }
+        put(self, snapshot.projects);
+        put(self, snapshot.areas);
+        put(self, snapshot.people);
+        put(self, snapshot.milestones);
+        put(self, snapshot.statuses);
+        put(self, snapshot.categories);
+        put(self, snapshot.priorities);
+    }
+    /// Cached lists, including expired ones.
+    pub fn snapshot(&self) -> MetadataSnapshot {
+        fn take<T: MetadataRecord>(records: &sealed::Records) -> Option<Vec<T>> {
+            T::slot(records)
+                .as_ref()
+                .map(|(_, records)| records.as_ref().clone())
+        }
+        let records = self.records.read().unwrap();
+        MetadataSnapshot {
+            projects: take(&records),
+            areas: take(&records),
+            people: take(&records),
+            milestones: take(&records),
+            statuses: take(&records),
+            categories: take(&records),
+            priorities: take(&records),
+        }
+    }
+    /// Cached list of `T`, unless it is missing or older than the TTL.
+    pub fn get<T: MetadataRecord>(&self) -> Option<Arc<Vec<T>>> {
+        let records = self.records.read().unwrap();
+        T::slot(&records)
+            .as_ref()
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl)
+            .map(|(_, records)| Arc::clone(records))
+    }
+    pub fn insert<T: MetadataRecord>(&self, records: Vec<T>) -> Arc<Vec<T>> {
+        let records = Arc::new(records);
+        *T::slot_mut(&mut self.records.write().unwrap()) =
+            Some((Instant::now(), Arc::clone(&records)));
+        records
+    }
+    /// Drops the cached list of `T`.
+    pub fn invalidate<T: MetadataRecord>(&self) {
+        *T::slot_mut(&mut self.records.write().unwrap()) = None;
+    }
+    /// Drops every cached list.
+    pub fn invalidate_all(&self) {
+        *self.records.write().unwrap() = sealed::Records::default();
+    }
+}
+
+/// Metadata record that can be fetched, cached and looked up by id or name.
+#[async_trait]
+pub trait MetadataRecord: sealed::Cached + Clone + Send + Sync + Sized + 'static {
+    fn id(&self) -> u64;
+    fn name(&self) -> &str;
+    /// Whether `name` refers to this record, ignoring ASCII case.
+    fn matches_name(&self, name: &str) -> bool {
+        self.name().eq_ignore_ascii_case(name)
+    }
+    /// Fetches the full list from the server.
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError>;
+}
+
+mod sealed {
+    use std::{sync::Arc, time::Instant};
+
+    use crate::metadata::{
+        Area, CategoryInfo, Milestone, Person, PriorityInfo, Project, StatusInfo,
+    };
+
+    pub type Slot<T> = Option<(Instant, Arc<Vec<T>>)>;
+
+    #[derive(Debug, Default)]
+    pub struct Records {
+        pub projects: Slot<Project>,
+        pub areas: Slot<Area>,
+        pub people: Slot<Person>,
+        pub milestones: Slot<Milestone>,
+        pub statuses: Slot<StatusInfo>,
+        pub categories: Slot<CategoryInfo>,
+        pub priorities: Slot<PriorityInfo>,
+    }
+
+    pub trait Cached: Sized {
+        fn slot(records: &Records) -> &Slot<Self>;
+        fn slot_mut(records: &mut Records) -> &mut Slot<Self>;
+    }
+
+    macro_rules! cached {
+        ($type:ty, $field:ident) => {
+            impl Cached for $type {
+                fn slot(records: &Records) -> &Slot<Self> {
+                    &records.$field
+                }
+                fn slot_mut(records: &mut Records) -> &mut Slot<Self> {
+                    &mut records.$field
+                }
+            }
+        };
+    }
+
+    cached!(Project, projects);
+    cached!(Area, areas);
+    cached!(Person, people);
+    cached!(Milestone, milestones);
+    cached!(StatusInfo, statuses);
+    cached!(CategoryInfo, categories);
+    cached!(PriorityInfo, priorities);
+}
+
+#[async_trait]
+impl MetadataRecord for Project {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError> {
+        metadata::list_all(api, "api/listProjects", "projects").await
+    }
+}
+
+#[async_trait]
+impl MetadataRecord for Area {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError> {
+        metadata::list_all(api, "api/listAreas", "areas").await
+    }
+}
+
+#[async_trait]
+impl MetadataRecord for Person {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.full_name
+    }
+    /// Matches the full name or the email address.
+    fn matches_name(&self, name: &str) -> bool {
+        self.full_name.eq_ignore_ascii_case(name)
+            || self
+                .email
+                .as_deref()
+                .is_some_and(|email| email.eq_ignore_ascii_case(name))
+    }
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError> {
+        metadata::list_all(api, "api/listPeople", "people").await
+    }
+}
+
+#[async_trait]
+impl MetadataRecord for Milestone {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError> {
+        metadata::list_all(api, "api/listFixFors", "fixfors").await
+    }
+}
+
+#[async_trait]
+impl MetadataRecord for StatusInfo {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError> {
+        metadata::list_all(api, "api/listStatuses", "statuses").await
+    }
+}
+
+#[async_trait]
+impl MetadataRecord for CategoryInfo {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError> {
+        metadata::list_all(api, "api/listCategories", "categories").await
+    }
+}
+
+#[async_trait]
+impl MetadataRecord for PriorityInfo {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn fetch(api: &FogbugzApi) -> Result<Vec<Self>, ResponseError> {
+        metadata::list_all(api, "api/listPriorities", "priorities").await
+    }
+}
+
+impl FogbugzApi {
+    pub fn cache(&self) -> Option<&MetadataCache> {
+        self.cache.as_deref()
+    }
+    /// Full list of `T`, from the cache when it is fresh.
+    pub async fn metadata<T: MetadataRecord>(&self) -> Result<Arc<Vec<T>>, ResponseError> {
+        let Some(cache) = self.cache() else {
+            return Ok(Arc::new(T::fetch(self).await?));
+        };
+        if let Some(records) = cache.get::<T>() {
+            return Ok(records);
+        }
+        Ok(cache.insert(T::fetch(self).await?))
+    }
+    pub async fn find_by_id<T: MetadataRecord>(&self, id: u64) -> Result<Option<T>, ResponseError> {
+        let records = self.metadata::<T>().await?;
+        Ok(records.iter().find(|record| record.id() == id).cloned())
+    }
+    /// Every record matching `name`, see [`MetadataRecord::matches_name`].
+    pub async fn find_by_name<T: MetadataRecord>(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Result<Vec<T>, ResponseError> {
+        let records = self.metadata::<T>().await?;
+        Ok(records
+            .iter()
+            .filter(|record| record.matches_name(name.as_ref()))
+            .cloned()
+            .collect())
+    }
+    /// Drops all cached metadata, including the statuses.
+    pub fn invalidate_cache(&self) {
+        if let Some(cache) = self.cache() {
+            cache.invalidate_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_api_builder;
+
+    const SNAPSHOT: &str = r#"{
+        "projects": [
+            {"ixProject": 1, "sProject": "Inbox", "fInbox": true},
+            {"ixProject": 2, "sProject": "Website"}
+        ],
+        "people": [
+            {"ixPerson": 1, "sFullName": "Administrator", "sEmail": "admin@example.com"},
+            {"ixPerson": 7, "sFullName": "Jane Doe", "sEmail": "jane@example.com"}
+        ]
+    }"#;
+
+    fn api(cache: MetadataCache) -> FogbugzApi {
+        test_api_builder().cache(cache).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_lookups_from_seeded_cache() {
+        let api = api(MetadataCache::from_json(DEFAULT_TTL, SNAPSHOT).unwrap()).clone();
+        let project = api.find_by_id::<Project>(2).await.unwrap().unwrap();
+        assert_eq!(project.name, "Website");
+        let people = api
+            .find_by_name::<Person>("JANE@example.com")
+            .await
+            .unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].id, 7);
+        assert!(api
+            .find_by_name::<Person>("Nobody")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ttl_and_invalidation() {
+        let cache = MetadataCache::from_json(Duration::ZERO, SNAPSHOT).unwrap();
+        assert!(cache.get::<Project>().is_none());
+        assert_eq!(cache.snapshot().projects.map(|p| p.len()), Some(2));
+
+        let cache = MetadataCache::from_json(DEFAULT_TTL, SNAPSHOT).unwrap();
+        assert!(cache.get::<Area>().is_none());
+        cache.invalidate::<Project>();
+        assert!(cache.get::<Project>().is_none());
+        assert!(cache.get::<Person>().is_some());
+        cache.invalidate_all();
+        assert_eq!(cache.snapshot(), MetadataSnapshot::default());
+    }
+}
@@ -1,12 +1,12 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Utc};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
-use serde_repr::Deserialize_repr;
 use thiserror::Error;
 
 use crate::{
-    enums::{Category, Column, Priority, Status},
-    FogbugzApi, ResponseError,
+    custom_fields::{self, CustomFieldValues},
+    enums::{Category, Column, Priority, ResolveStatus, Status, StatusRegistry},
+    search, FogbugzApi, ResponseError,
 };
 
 #[derive(Debug, Serialize)]
@@ -27,21 +27,40 @@ pub struct CaseDetailsRequestBuilder {
     api: Option<FogbugzApi>,
 }
 
+/// Columns required to deserialize a [`CaseDetails`].
+pub(crate) fn default_cols() -> Vec<String> {
+    vec![
+        Column::CaseId.to_string(),
+        Column::Title.to_string(),
+        Column::Events.to_string(),
+        Column::Project.to_string(),
+        Column::Area.to_string(),
+        Column::Priority.to_string(),
+        Column::Status.to_string(),
+        Column::Category.to_string(),
+        Column::IsOpen.to_string(),
+        Column::ParentId.to_string(),
+        Column::ChildIds.to_string(),
+    ]
+}
+
+/// [`default_cols`] followed by the `extra` columns it doesn't already list, so
+/// commands answering with a [`CaseDetails`] can't narrow away its fields.
+pub(crate) fn with_default_cols(extra: Vec<String>) -> Vec<String> {
+    let mut cols = default_cols();
+    for col in extra {
+        if !cols.contains(&col) {
+            cols.push(col);
+        }
+    }
+    cols
+}
+
 impl Default for CaseDetailsRequestBuilder {
     fn default() -> Self {
         Self {
             case_id: None,
-            cols: Some(vec![
-                Column::CaseId.to_string(),
-                Column::Title.to_string(),
-                Column::Events.to_string(),
-                Column::Project.to_string(),
-                Column::Area.to_string(),
-                Column::Priority.to_string(),
-                Column::Status.to_string(),
-                Column::Category.to_string(),
-                Column::IsOpen.to_string(),
-            ]),
+            cols: Some(default_cols()),
             api: None,
         }
     }
@@ -150,7 +169,39 @@ pub struct CaseDetails {
     pub priority: Priority,
     #[serde(rename = "ixCategory")]
     pub category: Category,
+    /// `None` for cases without a parent.
+    #[serde(
+        rename = "ixBugParent",
+        default,
+        deserialize_with = "search::parent_id"
+    )]
+    pub parent_id: Option<u64>,
+    #[serde(
+        rename = "ixBugChildren",
+        default,
+        deserialize_with = "search::id_list"
+    )]
+    pub child_ids: Option<Vec<u64>>,
     pub events: Vec<Event>,
+    /// Values of the requested [`Column::Custom`] columns.
+    #[serde(flatten, deserialize_with = "custom_fields::custom_field_values")]
+    pub custom_fields: CustomFieldValues,
+}
+
+impl CaseDetails {
+    /// Drops non-object entries from `events` before deserializing.
+    pub(crate) fn from_value(mut value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        if let serde_json::Value::Array(events) = &mut value["events"] {
+            events.retain(|event| matches!(event, serde_json::Value::Object(_)));
+        }
+        serde_json::from_value(value)
+    }
+}
+
+impl ResolveStatus for CaseDetails {
+    fn resolve_status(&mut self, registry: &StatusRegistry) {
+        self.status.resolve_status(registry);
+    }
 }
 
 impl CaseDetailsRequestBuilder {
@@ -213,11 +264,8 @@ impl CaseDetailsRequest {
 
         if response.status().is_success() {
             let mut json: serde_json::Value = response.json().await?;
-            if let serde_json::Value::Array(events) = &mut json["data"]["cases"][0]["events"] {
-                events.retain(|event| matches!(event, serde_json::Value::Object(_)));
-            }
-            let case_details =
-                serde_json::from_value::<CaseDetails>(json["data"]["cases"][0].take())?;
+            let mut case_details = CaseDetails::from_value(json["data"]["cases"][0].take())?;
+            self.api.resolve_statuses(&mut case_details).await;
             Ok(case_details)
         } else {
             let json: serde_json::Value = response.json().await?;
@@ -232,6 +280,7 @@ mod tests {
     use crate::FogbugzApiBuilder;
 
     #[tokio::test]
+    #[ignore = "requires FOGBUGZ_API_KEY and network access"]
     async fn test_case_details_request() {
         let api_key = std::env::var("FOGBUGZ_API_KEY").unwrap();
         #[cfg(feature = "leaky-bucket")]
@@ -239,12 +288,12 @@ mod tests {
             .initial(1)
             .interval(std::time::Duration::from_secs(1))
             .build();
-        let api = FogbugzApiBuilder::new()
+        let builder = FogbugzApiBuilder::new()
             .url("https://retailic.fogbugz.com")
-            .api_key(api_key)
-            .limiter(limiter)
-            .build()
-            .unwrap();
+            .api_key(api_key);
+        #[cfg(feature = "leaky-bucket")]
+        let builder = builder.limiter(limiter);
+        let api = builder.build().unwrap();
         let request = api
             .case_details()
             .case_id(61331)
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{enums::Column, query::Param, send_command, FogbugzApi, ResponseError};
+
+#[derive(Debug, Serialize)]
+pub struct ListCustomFieldsRequest {
+    #[serde(rename = "ixBug", skip_serializing_if = "Option::is_none")]
+    case_id: Option<u64>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListCustomFieldsRequestBuilder {
+    case_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListCustomFieldsRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+/// Custom field defined on the server, as returned by `listCustomFields`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomField {
+    /// Display name, used as the search axis.
+    #[serde(rename = "sName")]
+    pub name: String,
+    /// API column name, e.g. `plugin_customfields_at_fogcreek_com_customerx12`.
+    #[serde(rename = "sColumnName")]
+    pub column_name: String,
+    #[serde(rename = "sType", default)]
+    pub field_type: Option<String>,
+    /// Allowed values for choice fields.
+    #[serde(rename = "rgsChoices", default)]
+    pub choices: Vec<String>,
+}
+
+impl CustomField {
+    /// Column to request in `cols` to get the value of this field.
+    pub fn column(&self) -> Column {
+        Column::Custom(self.column_name.clone())
+    }
+    /// Search term matching cases where this field equals `value`.
+    pub fn matches(&self, value: impl AsRef<str>) -> Param {
+        Param::Other(self.name.clone(), value.as_ref().to_string())
+    }
+}
+
+/// The API column name, so a field can be passed to
+/// [`NewCaseRequestBuilder::custom_field`](crate::new_case::NewCaseRequestBuilder::custom_field)
+/// and [`EditCaseRequestBuilder::custom_field`](crate::edit_case::EditCaseRequestBuilder::custom_field).
+impl AsRef<str> for CustomField {
+    fn as_ref(&self) -> &str {
+        &self.column_name
+    }
+}
+
+impl From<&CustomField> for Column {
+    fn from(field: &CustomField) -> Self {
+        field.column()
+    }
+}
+
+/// Value of a custom field column on a case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CustomFieldValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+    List(Vec<String>),
+    /// Any other JSON the server sends for the field, kept as is.
+    Other(serde_json::Value),
+}
+
+impl CustomFieldValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            CustomFieldValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+    pub fn is_null(&self) -> bool {
+        matches!(self, CustomFieldValue::Null)
+    }
+}
+
+/// Custom field values of a case keyed by column name.
+pub type CustomFieldValues = BTreeMap<String, CustomFieldValue>;
+
+/// Collects the columns of a case object that are not covered by a typed
+/// field and parse as [`Column::Custom`]. Meant for `#[serde(flatten)]`.
+pub(crate) fn custom_field_values<'de, D>(deserializer: D) -> Result<CustomFieldValues, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let values = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    values
+        .into_iter()
+        .filter(|(key, _)| key != "operations")
+        .filter(|(key, _)| matches!(key.parse::<Column>(), Ok(Column::Custom(_))))
+        .map(|(key, value)| {
+            serde_json::from_value(value)
+                .map(|value| (key, value))
+                .map_err(serde::de::Error::custom)
+        })
+        .collect()
+}
+
+impl ListCustomFieldsRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Limits the listing to the fields available on the given case.
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListCustomFieldsRequest, ListCustomFieldsRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListCustomFieldsRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListCustomFieldsRequest {
+            case_id: self.case_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListCustomFieldsRequest {
+    pub fn builder() -> ListCustomFieldsRequestBuilder {
+        ListCustomFieldsRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<CustomField>, ResponseError> {
+        let mut json = send_command(&self.api, "api/listCustomFields", self).await?;
+        let fields = serde_json::from_value(json["data"]["customFields"].take())?;
+        Ok(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Query;
+
+    fn customer() -> CustomField {
+        serde_json::from_value(serde_json::json!({
+            "sName": "Customer",
+            "sColumnName": "plugin_customfields_at_fogcreek_com_customerx12",
+            "sType": "text",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_custom_field_column_and_query() {
+        let field = customer();
+        assert_eq!(
+            field.column().to_string(),
+            "plugin_customfields_at_fogcreek_com_customerx12"
+        );
+        assert!(field.choices.is_empty());
+        assert_eq!(
+            field.matches("Big Corp").to_string(),
+            "Customer:\"Big Corp\""
+        );
+
+        let field = CustomField {
+            name: "Support Contract".to_string(),
+            ..customer()
+        };
+        assert_eq!(
+            field.matches("Gold").to_string(),
+            "\"Support Contract\":Gold"
+        );
+        let query = Query::builder().custom_field(&field, "Gold").build();
+        assert_eq!(query.to_string().parse::<Query>().unwrap(), query);
+    }
+
+    #[test]
+    fn test_custom_field_values() {
+        #[derive(Deserialize)]
+        struct Case {
+            #[serde(rename = "ixBug")]
+            _case_id: u64,
+            #[serde(flatten, deserialize_with = "custom_field_values")]
+            custom_fields: CustomFieldValues,
+        }
+        let case: Case = serde_json::from_value(serde_json::json!({
+            "ixBug": 1,
+            "sTitle": "Ignored known column",
+            "operations": ["edit"],
+            "plugin_customfields_at_fogcreek_com_customerx12": "ACME",
+            "plugin_customfields_at_fogcreek_com_severityd34": null,
+            "plugin_customfields_at_fogcreek_com_scoresx56": [1, 2],
+            "plugin_customfields_at_fogcreek_com_addressx78": {"city": "Prague"},
+        }))
+        .unwrap();
+        assert_eq!(case.custom_fields.len(), 4);
+        assert_eq!(
+            case.custom_fields["plugin_customfields_at_fogcreek_com_customerx12"].as_str(),
+            Some("ACME")
+        );
+        assert!(case.custom_fields["plugin_customfields_at_fogcreek_com_severityd34"].is_null());
+        assert_eq!(
+            case.custom_fields["plugin_customfields_at_fogcreek_com_scoresx56"],
+            CustomFieldValue::Other(serde_json::json!([1, 2]))
+        );
+        assert_eq!(
+            case.custom_fields["plugin_customfields_at_fogcreek_com_addressx78"],
+            CustomFieldValue::Other(serde_json::json!({"city": "Prague"}))
+        );
+    }
+}
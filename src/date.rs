@@ -4,7 +4,7 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PointInTime {
     pub day: u32,
     pub month: u32,
@@ -17,7 +17,7 @@ impl PointInTime {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DateRange {
     pub start: PointInTime,
     pub end: PointInTime,
@@ -41,7 +41,7 @@ impl From<(PointInTime, PointInTime)> for Date {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Date {
     PointInTime(PointInTime),
     DateRange(DateRange),
@@ -51,10 +51,10 @@ impl FromStr for PointInTime {
     type Err = ParseIntError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split('-').collect();
-        let day = parts[0].parse::<u32>()?;
-        let month = parts[1].parse::<u32>()?;
-        let year = parts[2].parse::<u32>()?;
+        let mut parts = s.split('-');
+        let day = parts.next().unwrap_or_default().parse::<u32>()?;
+        let month = parts.next().unwrap_or_default().parse::<u32>()?;
+        let year = parts.next().unwrap_or_default().parse::<u32>()?;
         Ok(PointInTime { day, month, year })
     }
 }
@@ -62,9 +62,9 @@ impl FromStr for PointInTime {
 impl FromStr for DateRange {
     type Err = ParseIntError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split("..").collect();
-        let start = parts[0].parse::<PointInTime>()?;
-        let end = parts[1].parse::<PointInTime>()?;
+        let mut parts = s.split("..");
+        let start = parts.next().unwrap_or_default().parse::<PointInTime>()?;
+        let end = parts.next().unwrap_or_default().parse::<PointInTime>()?;
         Ok(DateRange { start, end })
     }
 }
@@ -122,7 +122,7 @@ impl<'de> Deserialize<'de> for Date {
         D: serde::Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        Ok(s.parse::<Date>().unwrap())
+        s.parse::<Date>().map_err(serde::de::Error::custom)
     }
 }
 
@@ -161,6 +161,12 @@ mod tests {
         assert_eq!(date_range.end.year, 2020);
     }
 
+    #[test]
+    fn test_parse_incomplete_date() {
+        assert!("31-12".parse::<super::PointInTime>().is_err());
+        assert!("01-01-2020..".parse::<super::DateRange>().is_err());
+    }
+
     #[test]
     fn test_display_point_in_time() {
         let point_in_time = super::PointInTime {
@@ -0,0 +1,520 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+
+use crate::{send_command, FogbugzApi, ResponseError};
+
+/// Default number of topics [`ListDiscussionRequest::stream`] fetches at once.
+pub const DEFAULT_BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscussGroup {
+    #[serde(rename = "ixDiscussGroup")]
+    pub id: u64,
+    #[serde(rename = "sFullName")]
+    pub name: String,
+    #[serde(rename = "sTagLineHTML", default)]
+    pub tagline: Option<String>,
+}
+
+/// Topic as listed by `listDiscussion`, without its posts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicSummary {
+    #[serde(rename = "ixDiscussTopic")]
+    pub id: u64,
+    #[serde(rename = "sHeadline")]
+    pub headline: String,
+    #[serde(rename = "sFullName", default)]
+    pub author: Option<String>,
+    #[serde(rename = "dt", default)]
+    pub date: Option<DateTime<Utc>>,
+    #[serde(rename = "cPosts", default)]
+    pub post_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Topic {
+    #[serde(rename = "ixDiscussTopic")]
+    pub id: u64,
+    #[serde(rename = "sHeadline")]
+    pub headline: String,
+    /// Opening post first, then the replies in the order they were posted.
+    #[serde(default)]
+    pub posts: Vec<Post>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Post {
+    #[serde(rename = "ixDiscussTopic")]
+    pub id: u64,
+    #[serde(rename = "sFullName", default)]
+    pub author: Option<String>,
+    #[serde(rename = "sEmail", default)]
+    pub email: Option<String>,
+    /// HTML body of the post.
+    #[serde(rename = "sPost", default)]
+    pub body: String,
+    #[serde(rename = "dt")]
+    pub date: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDiscussGroupsRequest {
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListDiscussGroupsRequestBuilder {
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListDiscussGroupsRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListDiscussGroupsRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListDiscussGroupsRequest, ListDiscussGroupsRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListDiscussGroupsRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListDiscussGroupsRequest {
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListDiscussGroupsRequest {
+    pub fn builder() -> ListDiscussGroupsRequestBuilder {
+        ListDiscussGroupsRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<DiscussGroup>, ResponseError> {
+        let mut json = send_command(&self.api, "api/listDiscussGroups", self).await?;
+        let groups = serde_json::from_value(json["data"]["discussions"].take())?;
+        Ok(groups)
+    }
+}
+
+/// Topics of a discussion group, newest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListDiscussionRequest {
+    #[serde(rename = "ixDiscussGroup")]
+    group_id: u64,
+    #[serde(rename = "fList")]
+    list: bool,
+    #[serde(rename = "nDaysAgo", skip_serializing_if = "Option::is_none")]
+    days_ago: Option<u32>,
+    #[serde(skip)]
+    offset: usize,
+    #[serde(skip)]
+    max: Option<u32>,
+    #[serde(skip)]
+    batch_size: usize,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug)]
+pub struct ListDiscussionRequestBuilder {
+    group_id: Option<u64>,
+    days_ago: Option<u32>,
+    offset: usize,
+    max: Option<u32>,
+    batch_size: usize,
+    api: Option<FogbugzApi>,
+}
+
+impl Default for ListDiscussionRequestBuilder {
+    fn default() -> Self {
+        Self {
+            group_id: None,
+            days_ago: None,
+            offset: 0,
+            max: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+            api: None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListDiscussionRequestBuilderError {
+    #[error("Group id is not specified")]
+    GroupIdNotSpecified,
+    #[error("Batch size must be greater than zero")]
+    InvalidBatchSize,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListDiscussionRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn group_id(mut self, group_id: u64) -> Self {
+        self.group_id = Some(group_id);
+        self
+    }
+    /// Only topics started in the last `days_ago` days.
+    pub fn days_ago(mut self, days_ago: u32) -> Self {
+        self.days_ago = Some(days_ago);
+        self
+    }
+    /// Skips the first `offset` topics of the listing.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+    /// Caps the number of returned topics, counted from `offset`.
+    pub fn max(mut self, max: u32) -> Self {
+        self.max = Some(max);
+        self
+    }
+    /// Number of topics whose posts are fetched concurrently when streaming.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListDiscussionRequest, ListDiscussionRequestBuilderError> {
+        let group_id = self
+            .group_id
+            .ok_or(ListDiscussionRequestBuilderError::GroupIdNotSpecified)?;
+        if self.batch_size == 0 {
+            return Err(ListDiscussionRequestBuilderError::InvalidBatchSize);
+        }
+        let api = self
+            .api
+            .ok_or(ListDiscussionRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListDiscussionRequest {
+            group_id,
+            list: true,
+            days_ago: self.days_ago,
+            offset: self.offset,
+            max: self.max,
+            batch_size: self.batch_size,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListDiscussionRequest {
+    pub fn builder() -> ListDiscussionRequestBuilder {
+        ListDiscussionRequestBuilder::new()
+    }
+    /// Topics between `offset` and `offset + max`.
+    ///
+    /// `listDiscussion` has no paging of its own, the server returns every
+    /// topic of the group, or of the last `days_ago` days, in one response.
+    /// The window is cut from that listing, so narrow large groups with
+    /// `days_ago`.
+    pub async fn send(&self) -> Result<Vec<TopicSummary>, ResponseError> {
+        let mut json = send_command(&self.api, "api/listDiscussion", self).await?;
+        let topics: Vec<TopicSummary> =
+            serde_json::from_value(json["data"]["discussion"]["topics"].take())?;
+        Ok(window(topics, self.offset, self.max))
+    }
+    /// Streams the topics returned by [`ListDiscussionRequest::send`] with
+    /// their posts.
+    ///
+    /// The listing is fetched once, then each topic takes one request; the
+    /// topics are fetched `batch_size` at a time and yielded in listing
+    /// order. The stream ends after the first error.
+    pub fn stream(&self) -> impl Stream<Item = Result<Topic, ResponseError>> {
+        let request = self.clone();
+        let (tx, rx) = mpsc::channel(self.batch_size);
+        tokio::spawn(async move {
+            let topics = match request.send().await {
+                Ok(topics) => topics,
+                Err(err) => {
+                    let _ = tx.send(Err(err)).await;
+                    return;
+                }
+            };
+            for batch in topics.chunks(request.batch_size) {
+                let pending: Vec<_> = batch
+                    .iter()
+                    .map(|summary| {
+                        let view = ViewTopicRequest {
+                            group_id: request.group_id,
+                            topic_id: summary.id,
+                            token: request.token.clone(),
+                            api: request.api.clone(),
+                        };
+                        tokio::spawn(async move { view.send().await })
+                    })
+                    .collect();
+                for handle in pending {
+                    let topic = match handle.await {
+                        Ok(topic) => topic,
+                        Err(err) => Err(err.into()),
+                    };
+                    let failed = topic.is_err();
+                    if tx.send(topic).await.is_err() || failed {
+                        return;
+                    }
+                }
+            }
+        });
+        ReceiverStream::new(rx)
+    }
+}
+
+fn window(topics: Vec<TopicSummary>, offset: usize, max: Option<u32>) -> Vec<TopicSummary> {
+    let max = max.map_or(usize::MAX, |max| max as usize);
+    topics.into_iter().skip(offset).take(max).collect()
+}
+
+/// Topic with all of its posts.
+#[derive(Debug, Serialize)]
+pub struct ViewTopicRequest {
+    #[serde(rename = "ixDiscussGroup")]
+    group_id: u64,
+    #[serde(rename = "ixDiscussTopic")]
+    topic_id: u64,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ViewTopicRequestBuilder {
+    group_id: Option<u64>,
+    topic_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ViewTopicRequestBuilderError {
+    #[error("Group id is not specified")]
+    GroupIdNotSpecified,
+    #[error("Topic id is not specified")]
+    TopicIdNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ViewTopicRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn group_id(mut self, group_id: u64) -> Self {
+        self.group_id = Some(group_id);
+        self
+    }
+    pub fn topic_id(mut self, topic_id: u64) -> Self {
+        self.topic_id = Some(topic_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ViewTopicRequest, ViewTopicRequestBuilderError> {
+        let group_id = self
+            .group_id
+            .ok_or(ViewTopicRequestBuilderError::GroupIdNotSpecified)?;
+        let topic_id = self
+            .topic_id
+            .ok_or(ViewTopicRequestBuilderError::TopicIdNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(ViewTopicRequestBuilderError::ApiNotSpecified)?;
+        Ok(ViewTopicRequest {
+            group_id,
+            topic_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ViewTopicRequest {
+    pub fn builder() -> ViewTopicRequestBuilder {
+        ViewTopicRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Topic, ResponseError> {
+        let mut json = send_command(&self.api, "api/listDiscussion", self).await?;
+        let topic = serde_json::from_value(json["data"]["discussion"]["topic"].take())?;
+        Ok(topic)
+    }
+}
+
+/// Replies to a discussion topic as the current user.
+#[derive(Debug, Serialize)]
+pub struct PostReplyRequest {
+    #[serde(rename = "ixDiscussTopic")]
+    topic_id: u64,
+    #[serde(rename = "sPost")]
+    body: String,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct PostReplyRequestBuilder {
+    topic_id: Option<u64>,
+    body: Option<String>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum PostReplyRequestBuilderError {
+    #[error("Topic id is not specified")]
+    TopicIdNotSpecified,
+    #[error("Body is not specified")]
+    BodyNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl PostReplyRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn topic_id(mut self, topic_id: u64) -> Self {
+        self.topic_id = Some(topic_id);
+        self
+    }
+    /// HTML body of the reply.
+    pub fn body(mut self, body: impl AsRef<str>) -> Self {
+        self.body = Some(body.as_ref().to_string());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<PostReplyRequest, PostReplyRequestBuilderError> {
+        let topic_id = self
+            .topic_id
+            .ok_or(PostReplyRequestBuilderError::TopicIdNotSpecified)?;
+        let body = self
+            .body
+            .ok_or(PostReplyRequestBuilderError::BodyNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(PostReplyRequestBuilderError::ApiNotSpecified)?;
+        Ok(PostReplyRequest {
+            topic_id,
+            body,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl PostReplyRequest {
+    pub fn builder() -> PostReplyRequestBuilder {
+        PostReplyRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Post, ResponseError> {
+        let mut json = send_command(&self.api, "api/postDiscussion", self).await?;
+        let post = serde_json::from_value(json["data"]["post"].take())?;
+        Ok(post)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_api;
+
+    #[test]
+    fn test_discussion_request_serialization() {
+        let request = test_api()
+            .list_discussion()
+            .group_id(2)
+            .days_ago(30)
+            .max(10)
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "ixDiscussGroup": 2,
+                "fList": true,
+                "nDaysAgo": 30,
+                "token": "secret",
+            })
+        );
+        let res = test_api()
+            .list_discussion()
+            .group_id(2)
+            .batch_size(0)
+            .build();
+        assert!(matches!(
+            res,
+            Err(ListDiscussionRequestBuilderError::InvalidBatchSize)
+        ));
+        let res = test_api().post_reply().topic_id(5).build();
+        assert!(matches!(
+            res,
+            Err(PostReplyRequestBuilderError::BodyNotSpecified)
+        ));
+    }
+
+    #[test]
+    fn test_topic_window() {
+        let topics: Vec<TopicSummary> = (1..=5)
+            .map(|id| TopicSummary {
+                id,
+                headline: format!("Topic {}", id),
+                author: None,
+                date: None,
+                post_count: None,
+            })
+            .collect();
+        let ids = |topics: Vec<TopicSummary>| topics.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(window(topics.clone(), 0, None)), vec![1, 2, 3, 4, 5]);
+        assert_eq!(ids(window(topics.clone(), 1, Some(2))), vec![2, 3]);
+        assert_eq!(ids(window(topics.clone(), 4, Some(2))), vec![5]);
+        assert!(window(topics, 6, None).is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_topic() {
+        let json = serde_json::json!({
+            "ixDiscussTopic": 5,
+            "sHeadline": "Export fails",
+            "posts": [
+                {
+                    "ixDiscussTopic": 5,
+                    "sFullName": "Customer",
+                    "sEmail": "customer@example.com",
+                    "sPost": "<p>The export fails.</p>",
+                    "dt": "2024-01-08T09:00:00Z"
+                },
+                {
+                    "ixDiscussTopic": 6,
+                    "sFullName": "Jane Doe",
+                    "sPost": "<p>Fixed in 2.1.</p>",
+                    "dt": "2024-01-09T09:00:00Z"
+                }
+            ]
+        });
+        let topic = serde_json::from_value::<Topic>(json).unwrap();
+        assert_eq!(topic.posts.len(), 2);
+        assert_eq!(topic.posts[1].author.as_deref(), Some("Jane Doe"));
+        assert_eq!(topic.posts[1].email, None);
+    }
+}
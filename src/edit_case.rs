@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    attachments::{self, FileUpload},
+    case_details::{self, CaseDetails},
+    enums::{Category, Column, Priority},
+    FogbugzApi, ResponseError,
+};
+
+#[derive(Debug, Serialize)]
+pub struct EditCaseRequest {
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    #[serde(rename = "sTitle", skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(rename = "ixArea", skip_serializing_if = "Option::is_none")]
+    area_id: Option<u64>,
+    #[serde(rename = "sArea", skip_serializing_if = "Option::is_none")]
+    area: Option<String>,
+    #[serde(rename = "ixPriority", skip_serializing_if = "Option::is_none")]
+    priority: Option<Priority>,
+    #[serde(rename = "ixCategory", skip_serializing_if = "Option::is_none")]
+    category: Option<Category>,
+    #[serde(rename = "sTags", skip_serializing_if = "Option::is_none")]
+    tags: Option<String>,
+    #[serde(rename = "hrsCurrEst", skip_serializing_if = "Option::is_none")]
+    estimate: Option<f64>,
+    #[serde(flatten)]
+    custom_fields: BTreeMap<String, String>,
+    #[serde(rename = "sEvent", skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cols: Option<Vec<String>>,
+    #[serde(skip)]
+    attachments: Vec<FileUpload>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct EditCaseRequestBuilder {
+    case_id: Option<u64>,
+    title: Option<String>,
+    area_id: Option<u64>,
+    area: Option<String>,
+    priority: Option<Priority>,
+    category: Option<Category>,
+    tags: Option<Vec<String>>,
+    estimate: Option<f64>,
+    custom_fields: BTreeMap<String, String>,
+    event: Option<String>,
+    cols: Option<Vec<String>>,
+    attachments: Vec<FileUpload>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum EditCaseRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl EditCaseRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    pub fn title(mut self, title: impl AsRef<str>) -> Self {
+        self.title = Some(title.as_ref().to_string());
+        self
+    }
+    pub fn area_id(mut self, area_id: u64) -> Self {
+        self.area_id = Some(area_id);
+        self
+    }
+    pub fn area(mut self, area: impl AsRef<str>) -> Self {
+        self.area = Some(area.as_ref().to_string());
+        self
+    }
+    pub fn priority(mut self, priority: impl Into<Priority>) -> Self {
+        self.priority = Some(priority.into());
+        self
+    }
+    pub fn category(mut self, category: impl Into<Category>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+    /// Replaces the tags of the case; an empty slice clears them.
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.tags = Some(tags.iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn add_tag(mut self, tag: impl AsRef<str>) -> Self {
+        if let Some(tags) = &mut self.tags {
+            tags.push(tag.as_ref().to_string())
+        } else {
+            self.tags = Some(vec![tag.as_ref().to_string()]);
+        }
+        self
+    }
+    /// Current estimate in hours.
+    pub fn estimate(mut self, hours: f64) -> Self {
+        self.estimate = Some(hours);
+        self
+    }
+    /// Sets a custom field, given as a [`CustomField`](crate::custom_fields::CustomField)
+    /// or by its API column name, e.g. `plugin_customfields_at_fogcreek_com_customerx12`.
+    pub fn custom_field(mut self, field: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.custom_fields
+            .insert(field.as_ref().to_string(), value.as_ref().to_string());
+        self
+    }
+    pub fn event(mut self, event: impl AsRef<str>) -> Self {
+        self.event = Some(event.as_ref().to_string());
+        self
+    }
+    /// Extra columns to return; the columns [`CaseDetails`] needs are always
+    /// requested as well.
+    pub fn cols(mut self, cols: Vec<Column>) -> Self {
+        self.cols = Some(cols.into_iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn add_col(mut self, col: Column) -> Self {
+        if let Some(cols) = &mut self.cols {
+            cols.push(col.to_string())
+        } else {
+            self.cols = Some(vec![col.to_string()]);
+        }
+        self
+    }
+    /// Uploads the file with the command, switching the request to multipart.
+    pub fn attachment(mut self, file: FileUpload) -> Self {
+        self.attachments.push(file);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<EditCaseRequest, EditCaseRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(EditCaseRequestBuilderError::CaseIdNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(EditCaseRequestBuilderError::ApiNotSpecified)?;
+        Ok(EditCaseRequest {
+            case_id,
+            title: self.title,
+            area_id: self.area_id,
+            area: self.area,
+            priority: self.priority,
+            category: self.category,
+            tags: self.tags.map(|tags| tags.join(",")),
+            estimate: self.estimate,
+            custom_fields: self.custom_fields,
+            event: self.event,
+            cols: Some(case_details::with_default_cols(
+                self.cols.unwrap_or_default(),
+            )),
+            attachments: self.attachments,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl EditCaseRequest {
+    pub fn builder() -> EditCaseRequestBuilder {
+        EditCaseRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<CaseDetails, ResponseError> {
+        attachments::send_case_command(&self.api, "api/edit", self, &self.attachments).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{custom_fields::CustomField, test_api};
+
+    #[test]
+    fn test_edit_case_request_requires_case_id() {
+        let res = EditCaseRequestBuilder::new()
+            .api(test_api())
+            .title("x")
+            .build();
+        assert!(matches!(
+            res,
+            Err(EditCaseRequestBuilderError::CaseIdNotSpecified)
+        ));
+    }
+
+    #[test]
+    fn test_edit_case_request_serialization() {
+        let customer: CustomField = serde_json::from_value(serde_json::json!({
+            "sName": "Customer",
+            "sColumnName": "plugin_customfields_at_fogcreek_com_customerx12",
+        }))
+        .unwrap();
+        let request = test_api()
+            .edit_case()
+            .case_id(42)
+            .priority(3)
+            .tags(&[])
+            .estimate(1.5)
+            .custom_field(&customer, "ACME")
+            .event("Re-estimated")
+            .cols(vec![Column::CaseId, Column::Tags])
+            .attachment(FileUpload::new("crash.dmp", *b"dump"))
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "ixBug": 42,
+                "ixPriority": 3,
+                "sTags": "",
+                "hrsCurrEst": 1.5,
+                "plugin_customfields_at_fogcreek_com_customerx12": "ACME",
+                "sEvent": "Re-estimated",
+                "cols": [
+                    "ixBug", "sTitle", "events", "sProject", "sArea", "ixPriority",
+                    "ixStatus", "ixCategory", "fOpen", "ixBugParent", "ixBugChildren", "tags",
+                ],
+                "token": "secret",
+            })
+        );
+    }
+
+    #[test]
+    fn test_case_details_from_edit_response() {
+        let case = CaseDetails::from_value(serde_json::json!({
+            "ixBug": 42,
+            "sTitle": "Printer on fire",
+            "sProject": "Inbox",
+            "fOpen": true,
+            "sArea": "Misc",
+            "ixStatus": 1,
+            "ixPriority": 3,
+            "ixCategory": 1,
+            "ixBugParent": 7,
+            "ixBugChildren": "43,44",
+            "events": [null],
+            "operations": ["edit", "assign", "resolve"],
+        }))
+        .unwrap();
+        assert_eq!(case.case_id, 42);
+        assert_eq!(case.priority, Priority(3));
+        assert!(case.events.is_empty());
+        assert_eq!(case.parent_id, Some(7));
+        assert_eq!(case.child_ids, Some(vec![43, 44]));
+    }
+}
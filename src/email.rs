@@ -0,0 +1,223 @@
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    attachments::{self, FileUpload},
+    case_details::{self, CaseDetails},
+    enums::Column,
+    FogbugzApi, ResponseError,
+};
+
+/// Request shared by the commands that send mail from a case:
+/// `reply`, `forward` and `email`.
+#[derive(Debug, Serialize)]
+pub struct MailRequest {
+    #[serde(skip)]
+    command: MailCommand,
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    #[serde(rename = "sFrom", skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(rename = "sTo")]
+    to: String,
+    #[serde(rename = "sCC", skip_serializing_if = "Option::is_none")]
+    cc: Option<String>,
+    #[serde(rename = "sBCC", skip_serializing_if = "Option::is_none")]
+    bcc: Option<String>,
+    #[serde(rename = "sSubject", skip_serializing_if = "Option::is_none")]
+    subject: Option<String>,
+    #[serde(rename = "sEvent", skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cols: Option<Vec<String>>,
+    #[serde(skip)]
+    attachments: Vec<FileUpload>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum MailCommand {
+    /// Answer to the correspondent of the case.
+    #[strum(serialize = "reply")]
+    Reply,
+    /// Forward the case to someone else.
+    #[strum(serialize = "forward")]
+    Forward,
+    /// New outgoing email on the case.
+    #[strum(serialize = "email")]
+    Email,
+}
+
+#[derive(Debug)]
+pub struct MailRequestBuilder {
+    command: MailCommand,
+    case_id: Option<u64>,
+    from: Option<String>,
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    subject: Option<String>,
+    body: Option<String>,
+    cols: Option<Vec<String>>,
+    attachments: Vec<FileUpload>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum MailRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Recipient is not specified")]
+    RecipientNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl MailRequestBuilder {
+    pub fn new(command: MailCommand) -> Self {
+        Self {
+            command,
+            case_id: None,
+            from: None,
+            to: Vec::new(),
+            cc: Vec::new(),
+            bcc: Vec::new(),
+            subject: None,
+            body: None,
+            cols: None,
+            attachments: Vec::new(),
+            api: None,
+        }
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    /// Mailbox address the message is sent from, FogBugz picks the
+    /// mailbox of the case when not set.
+    pub fn from(mut self, mailbox: impl AsRef<str>) -> Self {
+        self.from = Some(mailbox.as_ref().to_string());
+        self
+    }
+    pub fn to(mut self, address: impl AsRef<str>) -> Self {
+        self.to.push(address.as_ref().to_string());
+        self
+    }
+    pub fn cc(mut self, address: impl AsRef<str>) -> Self {
+        self.cc.push(address.as_ref().to_string());
+        self
+    }
+    pub fn bcc(mut self, address: impl AsRef<str>) -> Self {
+        self.bcc.push(address.as_ref().to_string());
+        self
+    }
+    pub fn subject(mut self, subject: impl AsRef<str>) -> Self {
+        self.subject = Some(subject.as_ref().to_string());
+        self
+    }
+    pub fn body(mut self, body: impl AsRef<str>) -> Self {
+        self.body = Some(body.as_ref().to_string());
+        self
+    }
+    pub fn attachment(mut self, file: FileUpload) -> Self {
+        self.attachments.push(file);
+        self
+    }
+    /// Extra columns to return; the columns [`CaseDetails`] needs are always
+    /// requested as well.
+    pub fn cols(mut self, cols: Vec<Column>) -> Self {
+        self.cols = Some(cols.into_iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<MailRequest, MailRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(MailRequestBuilderError::CaseIdNotSpecified)?;
+        if self.to.is_empty() {
+            return Err(MailRequestBuilderError::RecipientNotSpecified);
+        }
+        let api = self.api.ok_or(MailRequestBuilderError::ApiNotSpecified)?;
+        let join = |addresses: Vec<String>| (!addresses.is_empty()).then(|| addresses.join(", "));
+        Ok(MailRequest {
+            command: self.command,
+            case_id,
+            from: self.from,
+            to: self.to.join(", "),
+            cc: join(self.cc),
+            bcc: join(self.bcc),
+            subject: self.subject,
+            body: self.body,
+            cols: Some(case_details::with_default_cols(
+                self.cols.unwrap_or_default(),
+            )),
+            attachments: self.attachments,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl MailRequest {
+    pub fn builder(command: MailCommand) -> MailRequestBuilder {
+        MailRequestBuilder::new(command)
+    }
+    pub async fn send(&self) -> Result<CaseDetails, ResponseError> {
+        let command = format!("api/{}", self.command);
+        attachments::send_case_command(&self.api, &command, self, &self.attachments).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_api;
+
+    #[test]
+    fn test_mail_request_requires_recipient() {
+        let res = test_api().reply().case_id(42).body("Thanks!").build();
+        assert!(matches!(
+            res,
+            Err(MailRequestBuilderError::RecipientNotSpecified)
+        ));
+    }
+
+    #[test]
+    fn test_mail_request_serialization() {
+        let request = test_api()
+            .forward()
+            .case_id(42)
+            .from("support@example.com")
+            .to("dev@example.com")
+            .to("qa@example.com")
+            .bcc("audit@example.com")
+            .subject("Crash on startup")
+            .body("See the attached dump.")
+            .attachment(FileUpload::new("crash.dmp", *b"dump"))
+            .cols(vec![Column::CaseId, Column::Tags])
+            .build()
+            .unwrap();
+        assert_eq!(request.command.to_string(), "forward");
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "ixBug": 42,
+                "sFrom": "support@example.com",
+                "sTo": "dev@example.com, qa@example.com",
+                "sBCC": "audit@example.com",
+                "sSubject": "Crash on startup",
+                "sEvent": "See the attached dump.",
+                "cols": [
+                    "ixBug", "sTitle", "events", "sProject", "sArea", "ixPriority",
+                    "ixStatus", "ixCategory", "fOpen", "ixBugParent", "ixBugChildren", "tags",
+                ],
+                "token": "secret",
+            })
+        );
+    }
+}
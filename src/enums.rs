@@ -1,255 +1,614 @@
-use std::fmt;
+use core::fmt;
+use std::collections::HashMap;
 
-use serde::{
-    de::{self, Visitor},
-    Deserialize, Serialize,
-};
-use serde_repr::Deserialize_repr;
-use strum::{AsRefStr, Display};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
 
-#[derive(Debug, AsRefStr, Display)]
+use crate::metadata::{CategoryInfo, PriorityInfo, StatusInfo};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Display, EnumIter, EnumString, IntoStaticStr)]
 pub enum Column {
     #[strum(serialize = "ixBug")]
     CaseId,
+    #[strum(serialize = "ixBugParent")]
+    ParentId,
+    #[strum(serialize = "ixBugChildren")]
+    ChildIds,
+    #[strum(serialize = "ixBugDuplicates")]
+    DuplicateIds,
+    #[strum(serialize = "ixBugOriginal")]
+    OriginalId,
+    #[strum(serialize = "ixRelatedBugs")]
+    RelatedIds,
+    #[strum(serialize = "tags")]
+    Tags,
+    #[strum(serialize = "fOpen")]
+    IsOpen,
     #[strum(serialize = "sTitle")]
     Title,
+    #[strum(serialize = "sOriginalTitle")]
+    OriginalTitle,
+    #[strum(serialize = "sLatestTextSummary")]
+    LatestTextSummary,
+    #[strum(serialize = "ixBugEventLatestText")]
+    LatestTextEventId,
     #[strum(serialize = "sHtmlBody")]
     Body,
-    #[strum(serialize = "events")]
-    Events,
-    #[strum(serialize = "sProject")]
-    Project,
     #[strum(serialize = "ixProject")]
     ProjectId,
+    #[strum(serialize = "sProject")]
+    Project,
+    #[strum(serialize = "ixArea")]
+    AreaId,
     #[strum(serialize = "sArea")]
     Area,
-    #[strum(serialize = "ixPriority")]
-    Priority,
+    #[strum(serialize = "ixGroup")]
+    GroupId,
+    #[strum(serialize = "ixPersonAssignedTo")]
+    AssignedToId,
+    #[strum(serialize = "sPersonAssignedTo")]
+    AssignedTo,
+    #[strum(serialize = "sEmailAssignedTo")]
+    AssignedToEmail,
+    #[strum(serialize = "ixPersonOpenedBy")]
+    OpenedById,
+    #[strum(serialize = "ixPersonResolvedBy")]
+    ResolvedById,
+    #[strum(serialize = "ixPersonClosedBy")]
+    ClosedById,
+    #[strum(serialize = "ixPersonLastEditedBy")]
+    LastEditedById,
     #[strum(serialize = "ixStatus")]
     Status,
+    #[strum(serialize = "sStatus")]
+    StatusName,
+    #[strum(serialize = "ixPriority")]
+    Priority,
+    #[strum(serialize = "sPriority")]
+    PriorityName,
     #[strum(serialize = "ixCategory")]
     Category,
-    #[strum(serialize = "fOpen")]
-    IsOpen,
+    #[strum(serialize = "sCategory")]
+    CategoryName,
+    #[strum(serialize = "ixFixFor")]
+    MilestoneId,
+    #[strum(serialize = "sFixFor")]
+    Milestone,
+    #[strum(serialize = "dtFixFor")]
+    MilestoneDate,
+    #[strum(serialize = "nFixForOrder")]
+    MilestoneOrder,
+    #[strum(serialize = "sVersion")]
+    Version,
+    #[strum(serialize = "sComputer")]
+    Computer,
+    #[strum(serialize = "hrsOrigEst")]
+    OriginalEstimate,
+    #[strum(serialize = "hrsCurrEst")]
+    CurrentEstimate,
+    #[strum(serialize = "hrsElapsed")]
+    Elapsed,
+    #[strum(serialize = "dblStoryPts")]
+    StoryPoints,
+    #[strum(serialize = "c")]
+    Occurrences,
+    #[strum(serialize = "sCustomerEmail")]
+    CustomerEmail,
+    #[strum(serialize = "ixMailbox")]
+    MailboxId,
+    #[strum(serialize = "dtOpened")]
+    Opened,
+    #[strum(serialize = "dtResolved")]
+    Resolved,
+    #[strum(serialize = "dtClosed")]
+    Closed,
+    #[strum(serialize = "dtDue")]
+    Due,
+    #[strum(serialize = "dtLastUpdated")]
+    LastUpdated,
+    #[strum(serialize = "ixBugEventLatest")]
+    LatestEventId,
+    #[strum(serialize = "ixBugEventLastView")]
+    LastViewedEventId,
+    #[strum(serialize = "dtLastView")]
+    LastViewed,
+    #[strum(serialize = "fReplied")]
+    IsReplied,
+    #[strum(serialize = "fForwarded")]
+    IsForwarded,
+    #[strum(serialize = "fSubscribed")]
+    IsSubscribed,
+    #[strum(serialize = "fStarred")]
+    IsStarred,
+    #[strum(serialize = "sTicket")]
+    Ticket,
+    #[strum(serialize = "ixDiscussTopic")]
+    DiscussTopicId,
+    #[strum(serialize = "sReleaseNotes")]
+    ReleaseNotes,
+    #[strum(serialize = "sScoutDescription")]
+    ScoutDescription,
+    #[strum(serialize = "sScoutMessage")]
+    ScoutMessage,
+    #[strum(serialize = "fScoutStopReporting")]
+    ScoutStopReporting,
+    #[strum(serialize = "dtLastOccurrence")]
+    LastOccurrence,
+    #[strum(serialize = "ixKanbanColumn")]
+    KanbanColumnId,
+    #[strum(serialize = "sKanbanColumn")]
+    KanbanColumn,
+    #[strum(serialize = "events")]
+    Events,
+    #[strum(serialize = "minievents")]
+    MiniEvents,
+    /// Custom field column by its API name, see
+    /// [`CustomField`](crate::custom_fields::CustomField). Unknown column
+    /// names parse into this variant.
+    #[strum(default)]
+    Custom(String),
+}
+
+/// Column name as sent to the API, the field name for [`Column::Custom`].
+impl AsRef<str> for Column {
+    fn as_ref(&self) -> &str {
+        match self {
+            Column::Custom(name) => name,
+            column => column.into(),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize_repr, strum::Display)]
-#[serde(rename_all = "lowercase")]
-#[repr(u8)]
-pub enum Category {
-    Bug = 1,
-    Feature = 2,
-    Inquiry = 3,
-    Schedule = 4,
-    Report = 5,
-    Emergency = 6,
+impl Column {
+    /// Type of the value this column holds in a case object.
+    pub fn value_type(&self) -> ColumnType {
+        match self {
+            Column::CaseId
+            | Column::ParentId
+            | Column::OriginalId
+            | Column::LatestTextEventId
+            | Column::ProjectId
+            | Column::AreaId
+            | Column::GroupId
+            | Column::AssignedToId
+            | Column::OpenedById
+            | Column::ResolvedById
+            | Column::ClosedById
+            | Column::LastEditedById
+            | Column::MilestoneId
+            | Column::MilestoneOrder
+            | Column::Occurrences
+            | Column::MailboxId
+            | Column::LatestEventId
+            | Column::LastViewedEventId
+            | Column::DiscussTopicId
+            | Column::KanbanColumnId => ColumnType::U64,
+            Column::ChildIds | Column::DuplicateIds | Column::RelatedIds => ColumnType::IdList,
+            Column::Tags => ColumnType::StringList,
+            Column::IsOpen
+            | Column::IsReplied
+            | Column::IsForwarded
+            | Column::IsSubscribed
+            | Column::IsStarred
+            | Column::ScoutStopReporting => ColumnType::Bool,
+            Column::Title
+            | Column::OriginalTitle
+            | Column::LatestTextSummary
+            | Column::Body
+            | Column::Project
+            | Column::Area
+            | Column::AssignedTo
+            | Column::AssignedToEmail
+            | Column::StatusName
+            | Column::PriorityName
+            | Column::CategoryName
+            | Column::Milestone
+            | Column::Version
+            | Column::Computer
+            | Column::CustomerEmail
+            | Column::Ticket
+            | Column::ReleaseNotes
+            | Column::ScoutDescription
+            | Column::ScoutMessage
+            | Column::KanbanColumn => ColumnType::String,
+            Column::Status => ColumnType::Status,
+            Column::Priority => ColumnType::Priority,
+            Column::Category => ColumnType::Category,
+            Column::MilestoneDate
+            | Column::Opened
+            | Column::Resolved
+            | Column::Closed
+            | Column::Due
+            | Column::LastUpdated
+            | Column::LastViewed
+            | Column::LastOccurrence => ColumnType::DateTime,
+            Column::OriginalEstimate
+            | Column::CurrentEstimate
+            | Column::Elapsed
+            | Column::StoryPoints => ColumnType::F64,
+            Column::Events => ColumnType::Events,
+            Column::MiniEvents => ColumnType::MiniEvents,
+            Column::Custom(_) => ColumnType::Custom,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize_repr, strum::Display)]
-#[repr(u8)]
-pub enum Priority {
-    Blocker = 1,
-    MuyImportante = 2,
-    ShouldDo = 3,
-    FixIfTime = 4,
-    OhWell = 5,
-    WhoCares = 6,
-    DontFix = 7,
+/// Shape of a column value in a case object, see [`Column::value_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    U64,
+    F64,
+    Bool,
+    String,
+    DateTime,
+    /// List of case ids, sent either as an array or a comma separated string.
+    IdList,
+    StringList,
+    Status,
+    Priority,
+    Category,
+    Events,
+    MiniEvents,
+    /// Custom field value, see
+    /// [`CustomFieldValue`](crate::custom_fields::CustomFieldValue).
+    Custom,
 }
 
-#[derive(Debug, strum::Display)]
+impl ColumnType {
+    /// Rust type the column value deserializes to.
+    pub fn rust_type(&self) -> &'static str {
+        match self {
+            ColumnType::U64 => "u64",
+            ColumnType::F64 => "f64",
+            ColumnType::Bool => "bool",
+            ColumnType::String => "String",
+            ColumnType::DateTime => "DateTime<Utc>",
+            ColumnType::IdList => "Vec<u64>",
+            ColumnType::StringList => "Vec<String>",
+            ColumnType::Status => "Status",
+            ColumnType::Priority => "Priority",
+            ColumnType::Category => "Category",
+            ColumnType::Events => "Vec<Event>",
+            ColumnType::MiniEvents => "Vec<serde_json::Value>",
+            ColumnType::Custom => "CustomFieldValue",
+        }
+    }
+    /// Checks whether a raw JSON value has this type. `null` is accepted,
+    /// FogBugz sends it for unset columns.
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        use serde_json::Value;
+        match (self, value) {
+            (_, Value::Null) => true,
+            (
+                ColumnType::U64 | ColumnType::Status | ColumnType::Priority | ColumnType::Category,
+                Value::Number(n),
+            ) => n.is_u64(),
+            (ColumnType::F64, Value::Number(_)) => true,
+            (ColumnType::Bool, Value::Bool(_)) => true,
+            (ColumnType::String, Value::String(_)) => true,
+            (ColumnType::DateTime, Value::String(s)) => s.parse::<DateTime<Utc>>().is_ok(),
+            (ColumnType::IdList, Value::Array(ids)) => ids.iter().all(Value::is_u64),
+            (ColumnType::IdList, Value::String(s)) => s
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .all(|s| s.trim().parse::<u64>().is_ok()),
+            (ColumnType::StringList, Value::Array(items)) => items.iter().all(Value::is_string),
+            (ColumnType::Events | ColumnType::MiniEvents, Value::Array(_)) => true,
+            (ColumnType::Custom, _) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Category of a case by `ixCategory`. Categories are defined per FogBugz
+/// instance, see [`CategoryInfo`] for their names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Category(pub u64);
+
+impl Category {
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for Category {
+    fn from(id: u64) -> Self {
+        Category(id)
+    }
+}
+
+impl From<&CategoryInfo> for Category {
+    fn from(info: &CategoryInfo) -> Self {
+        Category(info.id)
+    }
+}
+
+/// Priority of a case by `ixPriority`. Priorities are defined per FogBugz
+/// instance, see [`PriorityInfo`] for their names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Priority(pub u64);
+
+impl Priority {
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for Priority {
+    fn from(id: u64) -> Self {
+        Priority(id)
+    }
+}
+
+impl From<&PriorityInfo> for Priority {
+    fn from(info: &PriorityInfo) -> Self {
+        Priority(info.id)
+    }
+}
+
+/// Status of a case, resolved through the [`StatusRegistry`] of the api.
+///
+/// Status ids differ between FogBugz instances, so they are looked up in the
+/// registry loaded from `listStatuses`. A status deserializes from its id as
+/// [`Status::Unknown`] and stays so until [`Status::resolve`] finds it in the
+/// registry; the requests returning cases with statuses do that before
+/// returning them.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Status {
-    Active,
-    Resolved,
-    Approved,
-    Rejected,
-    WontReview,
-    AbandonedNoConsensus,
+    Known(StatusInfo),
+    Unknown(u64),
 }
+
+impl Status {
+    /// Looks the status up in `registry`, ids missing from it stay unknown.
+    pub fn resolve(self, registry: &StatusRegistry) -> Status {
+        match self {
+            Status::Unknown(id) => registry
+                .get(id)
+                .cloned()
+                .map_or(Status::Unknown(id), Status::Known),
+            known => known,
+        }
+    }
+    pub fn id(&self) -> u64 {
+        match self {
+            Status::Known(info) => info.id,
+            Status::Unknown(id) => *id,
+        }
+    }
+    pub fn info(&self) -> Option<&StatusInfo> {
+        match self {
+            Status::Known(info) => Some(info),
+            Status::Unknown(_) => None,
+        }
+    }
+    pub fn name(&self) -> Option<&str> {
+        self.info().map(|info| info.name.as_str())
+    }
+    pub fn category_id(&self) -> Option<u64> {
+        self.info().map(|info| info.category_id)
+    }
+    pub fn is_resolved(&self) -> bool {
+        self.info().is_some_and(|info| info.is_resolved)
+    }
+    pub fn is_work_done(&self) -> bool {
+        self.info().is_some_and(|info| info.is_work_done)
+    }
+    pub fn is_duplicate(&self) -> bool {
+        self.info().is_some_and(|info| info.is_duplicate)
+    }
+    pub fn is_deleted(&self) -> bool {
+        self.info().is_some_and(|info| info.is_deleted)
+    }
+    pub fn is_reactivate(&self) -> bool {
+        self.info().is_some_and(|info| info.is_reactivate)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Known(info) => write!(f, "{}", info.name),
+            Status::Unknown(id) => write!(f, "Unknown({})", id),
+        }
+    }
+}
+
+impl Serialize for Status {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u64(self.id())
+    }
+}
+
 impl<'de> Deserialize<'de> for Status {
     fn deserialize<D>(deserializer: D) -> Result<Status, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let status = i32::deserialize(deserializer)?;
-        match status {
-            1 | 17 | 20 | 23 | 26 | 33 | 36 | 37 | 40 => Ok(Status::Active),
-            2..=16 | 18 | 19 | 21 | 22 | 24 | 25 | 31 | 32 | 34 | 35 | 38 | 39 => {
-                Ok(Status::Resolved)
-            }
-            27 => Ok(Status::Approved),
-            28 => Ok(Status::Rejected),
-            29 => Ok(Status::WontReview),
-            30 => Ok(Status::AbandonedNoConsensus),
-            _ => Err(serde::de::Error::custom(format!(
-                "Unknown status type: {}",
-                status
-            ))),
+        u64::deserialize(deserializer).map(Status::Unknown)
+    }
+}
+
+/// Values holding [`Status`]es, resolved by
+/// [`SearchRequest::send_cases`](crate::search::SearchRequest::send_cases),
+/// [`SearchRequest::stream_cases`](crate::search::SearchRequest::stream_cases)
+/// and the requests returning a
+/// [`CaseDetails`](crate::case_details::CaseDetails).
+pub trait ResolveStatus {
+    fn resolve_status(&mut self, registry: &StatusRegistry);
+}
+
+impl ResolveStatus for Status {
+    fn resolve_status(&mut self, registry: &StatusRegistry) {
+        *self = self.clone().resolve(registry);
+    }
+}
+
+impl<T: ResolveStatus> ResolveStatus for Option<T> {
+    fn resolve_status(&mut self, registry: &StatusRegistry) {
+        if let Some(value) = self {
+            value.resolve_status(registry);
+        }
+    }
+}
+
+/// Statuses of a FogBugz instance by id, as returned by `listStatuses`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusRegistry {
+    statuses: HashMap<u64, StatusInfo>,
+}
+
+impl StatusRegistry {
+    pub fn new(statuses: impl IntoIterator<Item = StatusInfo>) -> Self {
+        Self {
+            statuses: statuses.into_iter().map(|info| (info.id, info)).collect(),
         }
     }
+    pub fn get(&self, id: u64) -> Option<&StatusInfo> {
+        self.statuses.get(&id)
+    }
+    /// Statuses with the given name; the same name is usually used by several
+    /// categories.
+    pub fn by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a StatusInfo> + 'a {
+        self.statuses
+            .values()
+            .filter(move |info| info.name.eq_ignore_ascii_case(name))
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &StatusInfo> {
+        self.statuses.values()
+    }
+    pub fn len(&self) -> usize {
+        self.statuses.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.statuses.is_empty()
+    }
+}
+
+impl FromIterator<StatusInfo> for StatusRegistry {
+    fn from_iter<T: IntoIterator<Item = StatusInfo>>(iter: T) -> Self {
+        Self::new(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_column_names_round_trip() {
+        for column in Column::iter().filter(|c| !matches!(c, Column::Custom(_))) {
+            assert_eq!(Column::from_str(column.as_ref()).unwrap(), column);
+            assert_eq!(column.as_ref(), column.to_string());
+        }
+        let custom = Column::from_str("plugin_customfields_at_fogcreek_com_customerx12").unwrap();
+        assert_eq!(
+            custom,
+            Column::Custom("plugin_customfields_at_fogcreek_com_customerx12".to_string())
+        );
+        assert_eq!(
+            custom.to_string(),
+            "plugin_customfields_at_fogcreek_com_customerx12"
+        );
+        assert_eq!(
+            custom.as_ref(),
+            "plugin_customfields_at_fogcreek_com_customerx12"
+        );
+        assert_eq!(custom.value_type(), ColumnType::Custom);
+        assert_eq!(Column::ChildIds.to_string(), "ixBugChildren");
+        assert_eq!(Column::MilestoneDate.to_string(), "dtFixFor");
+    }
+
+    #[test]
+    fn test_priority_and_category_take_any_id() {
+        let priority: Priority = serde_json::from_value(serde_json::json!(42)).unwrap();
+        assert_eq!(priority, Priority(42));
+        let category: Category = serde_json::from_value(serde_json::json!(17)).unwrap();
+        assert_eq!(category.id(), 17);
+        assert_eq!(serde_json::to_value(Priority::from(3)).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_column_value_types() {
+        assert_eq!(Column::CaseId.value_type().rust_type(), "u64");
+        assert_eq!(Column::Opened.value_type(), ColumnType::DateTime);
+        assert_eq!(Column::Priority.value_type(), ColumnType::Priority);
+        assert_eq!(Column::Tags.value_type().rust_type(), "Vec<String>");
+    }
+
+    #[test]
+    fn test_column_type_matches() {
+        use serde_json::json;
+        assert!(ColumnType::U64.matches(&json!(42)));
+        assert!(!ColumnType::U64.matches(&json!("42")));
+        assert!(ColumnType::DateTime.matches(&json!("2023-08-01T10:00:00Z")));
+        assert!(!ColumnType::DateTime.matches(&json!("yesterday")));
+        assert!(ColumnType::IdList.matches(&json!("1,2, 3")));
+        assert!(ColumnType::IdList.matches(&json!([1, 2])));
+        assert!(!ColumnType::IdList.matches(&json!("1,x")));
+        assert!(ColumnType::String.matches(&json!(null)));
+        assert!(!ColumnType::Bool.matches(&json!(1)));
+    }
+
+    fn registry() -> StatusRegistry {
+        let statuses: Vec<StatusInfo> =
+            serde_json::from_str(include_str!("../tests/fixtures/statuses.json")).unwrap();
+        statuses.into_iter().collect()
+    }
+
+    #[test]
+    fn test_status_registry() {
+        let registry = registry();
+        assert_eq!(registry.len(), 15);
+        let approved = registry.get(27).unwrap();
+        assert_eq!(approved.name, "Approved");
+        assert_eq!(approved.category_id, 5);
+        assert!(approved.is_resolved);
+        assert_eq!(registry.by_name("resolved (postponed)").count(), 2);
+    }
+
+    #[test]
+    fn test_deserialize_status_with_registry() {
+        let registry = registry();
+        let statuses: Vec<Status> =
+            serde_json::from_value::<Vec<Status>>(serde_json::json!([34, 1]))
+                .unwrap()
+                .into_iter()
+                .map(|status| status.resolve(&registry))
+                .collect();
+        assert_eq!(statuses[0].name(), Some("Resolved (Completed)"));
+        assert!(statuses[0].is_work_done());
+        assert_eq!(statuses[1], Status::Unknown(1));
+        assert_eq!(statuses[1].to_string(), "Unknown(1)");
+
+        // Until resolved every status is unknown.
+        let status: Status = serde_json::from_value(serde_json::json!(34)).unwrap();
+        assert_eq!(status, Status::Unknown(34));
+    }
+
+    #[tokio::test]
+    async fn test_cached_statuses_are_shared_by_clones() {
+        use crate::cache::MetadataCache;
+
+        let api = crate::test_api_builder()
+            .cache(MetadataCache::default())
+            .build()
+            .unwrap();
+        let other = api.clone();
+        api.cache()
+            .unwrap()
+            .insert(registry().iter().cloned().collect::<Vec<_>>());
+        let statuses = other.statuses().await.unwrap();
+        assert_eq!(statuses.get(40).map(|info| info.id), Some(40));
+    }
 }
-// //       {
-// //         "ixStatus": 26,
-// //         "sStatus": "Active",
-// //         "ixCategory": 5,
-// //         "fWorkDone": false,
-// //         "fResolved": false,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 0
-// //       },
-// //       {
-// //         "ixStatus": 27,
-// //         "sStatus": "Approved",
-// //         "ixCategory": 5,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 1
-// //       },
-// //       {
-// //         "ixStatus": 28,
-// //         "sStatus": "Rejected",
-// //         "ixCategory": 5,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 2
-// //       },
-// //       {
-// //         "ixStatus": 29,
-// //         "sStatus": "Won't Review",
-// //         "ixCategory": 5,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": true,
-// //         "fReactivate": false,
-// //         "iOrder": 3
-// //       },
-// //       {
-// //         "ixStatus": 30,
-// //         "sStatus": "Abandoned - No Consensus",
-// //         "ixCategory": 5,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 3
-// //       },
-// //       {
-// //         "ixStatus": 31,
-// //         "sStatus": "Resolved (Postponed)",
-// //         "ixCategory": 4,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": true,
-// //         "iOrder": 0
-// //       },
-// //       {
-// //         "ixStatus": 32,
-// //         "sStatus": "Resolved (Postponed)",
-// //         "ixCategory": 2,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": true,
-// //         "iOrder": 0
-// //       },
-// //       {
-// //         "ixStatus": 33,
-// //         "sStatus": "Active",
-// //         "ixCategory": 6,
-// //         "fWorkDone": false,
-// //         "fResolved": false,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 2
-// //       },
-// //       {
-// //         "ixStatus": 34,
-// //         "sStatus": "Resolved (Completed)",
-// //         "ixCategory": 6,
-// //         "fWorkDone": true,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 0
-// //       },
-// //       {
-// //         "ixStatus": 35,
-// //         "sStatus": "Resolved (Duplicate)",
-// //         "ixCategory": 6,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": true,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 1
-// //       },
-// //       {
-// //         "ixStatus": 36,
-// //         "sStatus": "Active new",
-// //         "ixCategory": 6,
-// //         "fWorkDone": false,
-// //         "fResolved": false,
-// //         "fDuplicate": false,
-// //         "fDeleted": true,
-// //         "fReactivate": false,
-// //         "iOrder": 1
-// //       },
-// //       {
-// //         "ixStatus": 37,
-// //         "sStatus": "Active",
-// //         "ixCategory": 7,
-// //         "fWorkDone": false,
-// //         "fResolved": false,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 0
-// //       },
-// //       {
-// //         "ixStatus": 38,
-// //         "sStatus": "Resolved (Completed)",
-// //         "ixCategory": 7,
-// //         "fWorkDone": true,
-// //         "fResolved": true,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 0
-// //       },
-// //       {
-// //         "ixStatus": 39,
-// //         "sStatus": "Resolved (Duplicate)",
-// //         "ixCategory": 7,
-// //         "fWorkDone": false,
-// //         "fResolved": true,
-// //         "fDuplicate": true,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 1
-// //       },
-// //       {
-// //         "ixStatus": 40,
-// //         "sStatus": "Active (waiting for pricing)",
-// //         "ixCategory": 3,
-// //         "fWorkDone": false,
-// //         "fResolved": false,
-// //         "fDuplicate": false,
-// //         "fDeleted": false,
-// //         "fReactivate": false,
-// //         "iOrder": 1
-// //       }
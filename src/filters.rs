@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{refs::ResolveError, send_command, FogbugzApi, ResponseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum FilterType {
+    Builtin,
+    Saved,
+    Shared,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Filter as returned by `listFilters`. Pass it to
+/// [`ListCasesRequestBuilder::filter`](crate::list_cases::ListCasesRequestBuilder::filter)
+/// to list the cases it matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Filter {
+    #[serde(rename = "sFilter")]
+    pub id: String,
+    #[serde(rename = "sName", alias = "#text", alias = "#cdata-section")]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub filter_type: FilterType,
+    /// `current` for the filter currently applied for the user.
+    #[serde(rename = "status", default)]
+    pub status: Option<String>,
+}
+
+impl Filter {
+    pub fn is_current(&self) -> bool {
+        self.status.as_deref() == Some("current")
+    }
+}
+
+impl AsRef<str> for Filter {
+    fn as_ref(&self) -> &str {
+        &self.id
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListFiltersRequest {
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListFiltersRequestBuilder {
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListFiltersRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListFiltersRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListFiltersRequest, ListFiltersRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListFiltersRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListFiltersRequest {
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListFiltersRequest {
+    pub fn builder() -> ListFiltersRequestBuilder {
+        ListFiltersRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Filter>, ResponseError> {
+        let mut json = send_command(&self.api, "api/listFilters", self).await?;
+        let filters = serde_json::from_value(json["data"]["filters"].take())?;
+        Ok(filters)
+    }
+}
+
+/// Makes a filter the current one of the user, as `listCases` and
+/// `search` without a query use it.
+#[derive(Debug, Serialize)]
+pub struct SetCurrentFilterRequest {
+    #[serde(rename = "sFilter")]
+    filter: String,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct SetCurrentFilterRequestBuilder {
+    filter: Option<String>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum SetCurrentFilterRequestBuilderError {
+    #[error("Filter is not specified")]
+    FilterNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl SetCurrentFilterRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Filter id, or a [`Filter`] returned by `listFilters`.
+    pub fn filter(mut self, filter: impl AsRef<str>) -> Self {
+        self.filter = Some(filter.as_ref().to_string());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<SetCurrentFilterRequest, SetCurrentFilterRequestBuilderError> {
+        let filter = self
+            .filter
+            .ok_or(SetCurrentFilterRequestBuilderError::FilterNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(SetCurrentFilterRequestBuilderError::ApiNotSpecified)?;
+        Ok(SetCurrentFilterRequest {
+            filter,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl SetCurrentFilterRequest {
+    pub fn builder() -> SetCurrentFilterRequestBuilder {
+        SetCurrentFilterRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<(), ResponseError> {
+        send_command(&self.api, "api/setCurrentFilter", self).await?;
+        Ok(())
+    }
+}
+
+impl FogbugzApi {
+    /// Filter with the given name, compared case-insensitively.
+    pub async fn find_filter(&self, name: impl AsRef<str>) -> Result<Filter, ResponseError> {
+        let request = ListFiltersRequest {
+            token: self.api_key.clone(),
+            api: self.clone(),
+        };
+        let filters = request.send().await?;
+        find_by_name(filters, name.as_ref())
+    }
+}
+
+fn find_by_name(filters: Vec<Filter>, name: &str) -> Result<Filter, ResponseError> {
+    let mut matches: Vec<Filter> = filters
+        .into_iter()
+        .filter(|filter| filter.name.eq_ignore_ascii_case(name))
+        .collect();
+    match matches.len() {
+        1 => Ok(matches.remove(0)),
+        0 => Err(ResolveError::NotFound {
+            kind: "filter",
+            name: name.to_string(),
+        }
+        .into()),
+        _ => Err(ResolveError::AmbiguousFilter {
+            name: name.to_string(),
+            ids: matches.into_iter().map(|filter| filter.id).collect(),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_api;
+
+    fn filters() -> Vec<Filter> {
+        serde_json::from_value(serde_json::json!([
+            {"type": "builtin", "sFilter": "ez", "sName": "My Cases", "status": "current"},
+            {"type": "saved", "sFilter": "12", "#text": "Support backlog"},
+            {"type": "shared", "sFilter": "14", "sName": "Release blockers"},
+            {"type": "shared", "sFilter": "15", "sName": "Release blockers"},
+            {"type": "builtin", "sFilter": "inbox", "sName": "Release blockers"},
+            {"type": "team", "sFilter": "16", "sName": "Team"}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_filters() {
+        let filters = filters();
+        assert!(filters[0].is_current());
+        assert_eq!(filters[1].name, "Support backlog");
+        assert_eq!(filters[1].filter_type, FilterType::Saved);
+        assert_eq!(filters[5].filter_type, FilterType::Unknown);
+
+        let request = test_api().list_cases().filter(&filters[1]).build().unwrap();
+        assert_eq!(serde_json::to_value(&request).unwrap()["sFilter"], "12");
+    }
+
+    #[test]
+    fn test_find_filter_by_name() {
+        let filter = find_by_name(filters(), "support BACKLOG").unwrap();
+        assert_eq!(filter.id, "12");
+        let err = find_by_name(filters(), "Release blockers").unwrap_err();
+        assert!(matches!(
+            err,
+            ResponseError::ResolveError(ResolveError::AmbiguousFilter { ref ids, .. })
+                if ids == &["14", "15", "inbox"]
+        ));
+    }
+}
@@ -0,0 +1,442 @@
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+};
+
+use thiserror::Error;
+
+use crate::{
+    enums::{Column, ResolveStatus},
+    refs::ResolveError,
+    search::{self, Case, DEFAULT_PAGE_SIZE},
+    FogbugzApi, ResponseError,
+};
+
+/// Walks the parent/child links of a case, see [`HierarchyRequest::subtree`]
+/// and [`HierarchyRequest::ancestors`].
+#[derive(Debug)]
+pub struct HierarchyRequest {
+    case_id: u64,
+    cols: Vec<Column>,
+    max_depth: Option<usize>,
+    page_size: usize,
+    api: FogbugzApi,
+}
+
+#[derive(Debug)]
+pub struct HierarchyRequestBuilder {
+    case_id: Option<u64>,
+    cols: Vec<Column>,
+    max_depth: Option<usize>,
+    page_size: usize,
+    api: Option<FogbugzApi>,
+}
+
+impl Default for HierarchyRequestBuilder {
+    fn default() -> Self {
+        Self {
+            case_id: None,
+            cols: vec![
+                Column::CaseId,
+                Column::Title,
+                Column::ParentId,
+                Column::ChildIds,
+                Column::IsOpen,
+                Column::OriginalEstimate,
+                Column::CurrentEstimate,
+                Column::Elapsed,
+            ],
+            max_depth: None,
+            page_size: DEFAULT_PAGE_SIZE,
+            api: None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum HierarchyRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Page size must be greater than zero")]
+    InvalidPageSize,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+/// Case with the cases below it.
+#[derive(Debug)]
+pub struct CaseTree {
+    pub case: Case,
+    pub children: Vec<CaseTree>,
+}
+
+/// Totals over a [`CaseTree`], estimates and elapsed time are in hours.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RollUp {
+    pub open: usize,
+    pub closed: usize,
+    pub original_estimate: f64,
+    pub current_estimate: f64,
+    pub elapsed: f64,
+}
+
+impl RollUp {
+    pub fn total(&self) -> usize {
+        self.open + self.closed
+    }
+    /// Share of closed cases, between 0 and 1.
+    pub fn progress(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.closed as f64 / total as f64,
+        }
+    }
+}
+
+impl CaseTree {
+    /// Cases in the tree, each parent before its children.
+    pub fn iter(&self) -> impl Iterator<Item = &Case> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(&node.case)
+        })
+    }
+    pub fn find(&self, case_id: u64) -> Option<&CaseTree> {
+        if self.case.case_id == case_id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(case_id))
+    }
+    /// Totals over the case and all of its descendants.
+    pub fn roll_up(&self) -> RollUp {
+        self.iter().fold(RollUp::default(), |mut roll_up, case| {
+            if case.is_open.unwrap_or(true) {
+                roll_up.open += 1;
+            } else {
+                roll_up.closed += 1;
+            }
+            roll_up.original_estimate += case.original_estimate.unwrap_or_default();
+            roll_up.current_estimate += case.current_estimate.unwrap_or_default();
+            roll_up.elapsed += case.elapsed.unwrap_or_default();
+            roll_up
+        })
+    }
+}
+
+impl HierarchyRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    /// Fetches another column for every case, next to the ones the
+    /// traversal and roll-up need.
+    pub fn add_col(mut self, col: Column) -> Self {
+        if !self.cols.contains(&col) {
+            self.cols.push(col);
+        }
+        self
+    }
+    /// Levels to descend or ascend, unlimited by default.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+    /// Number of cases fetched per request.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<HierarchyRequest, HierarchyRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(HierarchyRequestBuilderError::CaseIdNotSpecified)?;
+        if self.page_size == 0 {
+            return Err(HierarchyRequestBuilderError::InvalidPageSize);
+        }
+        let api = self
+            .api
+            .ok_or(HierarchyRequestBuilderError::ApiNotSpecified)?;
+        Ok(HierarchyRequest {
+            case_id,
+            cols: self.cols,
+            max_depth: self.max_depth,
+            page_size: self.page_size,
+            api,
+        })
+    }
+}
+
+impl HierarchyRequest {
+    pub fn builder() -> HierarchyRequestBuilder {
+        HierarchyRequestBuilder::new()
+    }
+    /// The case and everything below it. Each level is fetched in batches of
+    /// `page_size` cases, a case reachable twice is only included once.
+    pub async fn subtree(&self) -> Result<CaseTree, ResponseError> {
+        let mut cases = walk_subtree(self.case_id, self.max_depth, self.page_size, |batch| {
+            self.fetch(batch)
+        })
+        .await?;
+        build_tree(self.case_id, &mut cases).ok_or_else(|| self.not_found())
+    }
+    /// Parents of the case, nearest first. Stops at the root or when a parent
+    /// repeats.
+    pub async fn ancestors(&self) -> Result<Vec<Case>, ResponseError> {
+        walk_ancestors(self.case_id, self.max_depth, |batch| self.fetch(batch))
+            .await?
+            .ok_or_else(|| self.not_found())
+    }
+    /// Cases with the given ids, in a single request.
+    async fn fetch(&self, case_ids: Vec<u64>) -> Result<Vec<Case>, ResponseError> {
+        let cases = search::fetch_cases(&self.api, &case_ids, &self.cols).await?;
+        let mut cases = cases
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<Case>, _>>()?;
+        if self.cols.contains(&Column::Status) {
+            let statuses = self.api.statuses().await?;
+            cases
+                .iter_mut()
+                .for_each(|case| case.resolve_status(&statuses));
+        }
+        Ok(cases)
+    }
+    fn not_found(&self) -> ResponseError {
+        ResolveError::NotFound {
+            kind: "case",
+            name: self.case_id.to_string(),
+        }
+        .into()
+    }
+}
+
+/// Breadth-first walk down the child links. The ids of each level not seen
+/// before are passed to `fetch` in batches of `page_size`.
+async fn walk_subtree<F, Fut>(
+    case_id: u64,
+    max_depth: Option<usize>,
+    page_size: usize,
+    mut fetch: F,
+) -> Result<HashMap<u64, Case>, ResponseError>
+where
+    F: FnMut(Vec<u64>) -> Fut,
+    Fut: Future<Output = Result<Vec<Case>, ResponseError>>,
+{
+    let mut cases = HashMap::new();
+    let mut seen = HashSet::from([case_id]);
+    let mut level = vec![case_id];
+    let mut depth = 0;
+    while !level.is_empty() {
+        let descend = max_depth.is_none_or(|max| depth < max);
+        let mut next = Vec::new();
+        for batch in level.chunks(page_size) {
+            for case in fetch(batch.to_vec()).await? {
+                if descend {
+                    for child in case.child_ids.iter().flatten() {
+                        if seen.insert(*child) {
+                            next.push(*child);
+                        }
+                    }
+                }
+                cases.insert(case.case_id, case);
+            }
+        }
+        level = next;
+        depth += 1;
+    }
+    Ok(cases)
+}
+
+/// Walk up the parent links, `None` when the case itself is not found.
+async fn walk_ancestors<F, Fut>(
+    case_id: u64,
+    max_depth: Option<usize>,
+    mut fetch: F,
+) -> Result<Option<Vec<Case>>, ResponseError>
+where
+    F: FnMut(Vec<u64>) -> Fut,
+    Fut: Future<Output = Result<Vec<Case>, ResponseError>>,
+{
+    let Some(case) = fetch(vec![case_id]).await?.pop() else {
+        return Ok(None);
+    };
+    let mut seen = HashSet::from([case_id]);
+    let mut parent_id = case.parent_id;
+    let mut ancestors = Vec::new();
+    while let Some(id) = parent_id {
+        if !seen.insert(id) || max_depth.is_some_and(|max| ancestors.len() >= max) {
+            break;
+        }
+        let Some(parent) = fetch(vec![id]).await?.pop() else {
+            break;
+        };
+        parent_id = parent.parent_id;
+        ancestors.push(parent);
+    }
+    Ok(Some(ancestors))
+}
+
+/// Takes the cases out of `cases` while building the tree, so a case linked
+/// from several parents, or from its own descendants, appears only once.
+fn build_tree(case_id: u64, cases: &mut HashMap<u64, Case>) -> Option<CaseTree> {
+    let case = cases.remove(&case_id)?;
+    let children = case
+        .child_ids
+        .iter()
+        .flatten()
+        .filter_map(|child| build_tree(*child, cases))
+        .collect();
+    Some(CaseTree { case, children })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(case_id: u64, children: &[u64], is_open: bool, estimate: f64) -> Case {
+        serde_json::from_value(serde_json::json!({
+            "ixBug": case_id,
+            "ixBugChildren": children,
+            "fOpen": is_open,
+            "hrsCurrEst": estimate,
+            "hrsElapsed": estimate / 2.0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_tree_guards_cycles() {
+        let mut cases: HashMap<u64, Case> = [
+            case(1, &[2, 3], true, 0.0),
+            case(2, &[4, 1], false, 2.0),
+            case(3, &[4], true, 4.0),
+            case(4, &[], false, 1.0),
+        ]
+        .into_iter()
+        .map(|case| (case.case_id, case))
+        .collect();
+        let tree = build_tree(1, &mut cases).unwrap();
+        assert!(cases.is_empty());
+        assert_eq!(
+            tree.iter().map(|case| case.case_id).collect::<Vec<_>>(),
+            vec![1, 2, 4, 3]
+        );
+        assert!(tree.find(3).unwrap().children.is_empty());
+
+        let roll_up = tree.roll_up();
+        assert_eq!((roll_up.open, roll_up.closed), (2, 2));
+        assert_eq!(roll_up.current_estimate, 7.0);
+        assert_eq!(roll_up.elapsed, 3.5);
+        assert_eq!(roll_up.progress(), 0.5);
+    }
+
+    /// Serves cases from `store`, recording the ids asked for in each call.
+    fn fetcher<'a>(
+        store: &'a HashMap<u64, serde_json::Value>,
+        calls: &'a mut Vec<Vec<u64>>,
+    ) -> impl FnMut(Vec<u64>) -> std::future::Ready<Result<Vec<Case>, ResponseError>> + 'a {
+        |case_ids| {
+            let cases = case_ids
+                .iter()
+                .filter_map(|id| store.get(id))
+                .map(|json| serde_json::from_value(json.clone()).unwrap())
+                .collect();
+            calls.push(case_ids);
+            std::future::ready(Ok(cases))
+        }
+    }
+
+    fn store(cases: &[(u64, Option<u64>, &[u64])]) -> HashMap<u64, serde_json::Value> {
+        cases
+            .iter()
+            .map(|(case_id, parent_id, children)| {
+                let json = serde_json::json!({
+                    "ixBug": case_id,
+                    "ixBugParent": parent_id.unwrap_or(0),
+                    "ixBugChildren": children,
+                });
+                (*case_id, json)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_subtree_fetches_levels_in_batches() {
+        let children: Vec<u64> = (2..=8).collect();
+        let mut cases = vec![(1, None, children.as_slice())];
+        cases.extend(children.iter().map(|id| (*id, Some(1), &[][..])));
+        let store = store(&cases);
+        let mut calls = Vec::new();
+        let subtree = walk_subtree(1, None, 3, fetcher(&store, &mut calls))
+            .await
+            .unwrap();
+        assert_eq!(subtree.len(), 8);
+        // One request for the root, ceil(7 / 3) for its children.
+        assert_eq!(calls, vec![vec![1], vec![2, 3, 4], vec![5, 6, 7], vec![8]]);
+    }
+
+    #[tokio::test]
+    async fn test_subtree_stops_at_max_depth() {
+        let store = store(&[
+            (1, None, &[2]),
+            (2, Some(1), &[3]),
+            (3, Some(2), &[4]),
+            (4, Some(3), &[]),
+        ]);
+        let mut calls = Vec::new();
+        let subtree = walk_subtree(1, Some(1), 10, fetcher(&store, &mut calls))
+            .await
+            .unwrap();
+        let mut ids: Vec<u64> = subtree.into_keys().collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(calls, vec![vec![1], vec![2]]);
+
+        let mut calls = Vec::new();
+        let subtree = walk_subtree(1, Some(0), 10, fetcher(&store, &mut calls))
+            .await
+            .unwrap();
+        assert_eq!(subtree.len(), 1);
+        assert_eq!(calls.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ancestors_stop_at_parent_cycle() {
+        let store = store(&[(1, Some(2), &[]), (2, Some(3), &[1]), (3, Some(1), &[2])]);
+        let mut calls = Vec::new();
+        let ancestors = walk_ancestors(1, None, fetcher(&store, &mut calls))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ancestors
+                .iter()
+                .map(|case| case.case_id)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(calls, vec![vec![1], vec![2], vec![3]]);
+
+        let mut calls = Vec::new();
+        let ancestors = walk_ancestors(1, Some(1), fetcher(&store, &mut calls))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ancestors.len(), 1);
+
+        let mut calls = Vec::new();
+        let missing = walk_ancestors(9, None, fetcher(&store, &mut calls))
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+}
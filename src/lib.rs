@@ -1,11 +1,27 @@
+pub mod attachments;
+pub mod cache;
 pub mod case_details;
+pub mod custom_fields;
 pub mod date;
+pub mod discussion;
+pub mod edit_case;
+pub mod email;
 pub mod enums;
 pub mod export;
+pub mod filters;
+pub mod hierarchy;
 pub mod list_cases;
 pub mod list_intervals;
+pub mod metadata;
+pub mod new_case;
 pub mod query;
+pub mod refs;
 pub mod search;
+pub mod subscriptions;
+pub mod time_tracking;
+pub mod timesheet;
+pub mod wiki;
+pub mod workflow;
 
 use core::fmt;
 use std::sync::Arc;
@@ -14,6 +30,12 @@ use std::sync::Arc;
 use leaky_bucket::RateLimiter;
 use thiserror::Error;
 
+use crate::{
+    cache::MetadataCache,
+    enums::{ResolveStatus, StatusRegistry},
+    metadata::StatusInfo,
+};
+
 #[derive(Clone)]
 pub struct FogbugzApi {
     pub url: String,
@@ -21,6 +43,7 @@ pub struct FogbugzApi {
     #[cfg(feature = "leaky-bucket")]
     limiter: Arc<RateLimiter>,
     pub client: reqwest::Client,
+    cache: Option<Arc<MetadataCache>>,
 }
 
 impl fmt::Debug for FogbugzApi {
@@ -39,6 +62,7 @@ pub struct FogbugzApiBuilder {
     #[cfg(feature = "leaky-bucket")]
     limiter: Option<RateLimiter>,
     pub client: Option<reqwest::Client>,
+    cache: Option<MetadataCache>,
 }
 
 #[derive(Debug, Error)]
@@ -79,6 +103,11 @@ impl FogbugzApiBuilder {
         self.limiter = Some(limiter);
         self
     }
+    /// Caches metadata lists, see [`FogbugzApi::metadata`].
+    pub fn cache(mut self, cache: MetadataCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
     pub fn build(self) -> Result<FogbugzApi, FogbugzApiBuilderError> {
         let url = self.url.ok_or(FogbugzApiBuilderError::MissingUrl)?;
         let api_key = self.api_key.ok_or(FogbugzApiBuilderError::MissingApiKey)?;
@@ -91,11 +120,26 @@ impl FogbugzApiBuilder {
             #[cfg(feature = "leaky-bucket")]
             limiter: Arc::new(limiter),
             client,
+            cache: self.cache.map(Arc::new),
         })
     }
 }
 
 impl FogbugzApi {
+    /// Status registry of the instance, built from the `listStatuses` list
+    /// of the [`MetadataCache`] when one is set, fetched otherwise.
+    pub async fn statuses(&self) -> Result<StatusRegistry, ResponseError> {
+        let statuses = self.metadata::<StatusInfo>().await?;
+        Ok(statuses.iter().cloned().collect())
+    }
+    /// Resolves the statuses of a returned case. They stay unknown when
+    /// `listStatuses` fails, so a write that went through is not reported
+    /// as failed.
+    pub(crate) async fn resolve_statuses<T: ResolveStatus>(&self, case: &mut T) {
+        if let Ok(statuses) = self.statuses().await {
+            case.resolve_status(&statuses);
+        }
+    }
     pub fn list_cases(&self) -> list_cases::ListCasesRequestBuilder {
         list_cases::ListCasesRequestBuilder::new().api(self.clone())
     }
@@ -108,6 +152,164 @@ impl FogbugzApi {
     pub fn list_intervals(&self) -> list_intervals::ListIntervalsRequestBuilder {
         list_intervals::ListIntervalsRequestBuilder::new().api(self.clone())
     }
+    pub fn list_custom_fields(&self) -> custom_fields::ListCustomFieldsRequestBuilder {
+        custom_fields::ListCustomFieldsRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_projects(&self) -> metadata::ListProjectsRequestBuilder {
+        metadata::ListProjectsRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_areas(&self) -> metadata::ListAreasRequestBuilder {
+        metadata::ListAreasRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_people(&self) -> metadata::ListPeopleRequestBuilder {
+        metadata::ListPeopleRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_milestones(&self) -> metadata::ListMilestonesRequestBuilder {
+        metadata::ListMilestonesRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_statuses(&self) -> metadata::ListStatusesRequestBuilder {
+        metadata::ListStatusesRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_categories(&self) -> metadata::ListCategoriesRequestBuilder {
+        metadata::ListCategoriesRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_priorities(&self) -> metadata::ListPrioritiesRequestBuilder {
+        metadata::ListPrioritiesRequestBuilder::new().api(self.clone())
+    }
+    pub fn start_work(&self) -> time_tracking::StartWorkRequestBuilder {
+        time_tracking::StartWorkRequestBuilder::new().api(self.clone())
+    }
+    pub fn stop_work(&self) -> time_tracking::StopWorkRequestBuilder {
+        time_tracking::StopWorkRequestBuilder::new().api(self.clone())
+    }
+    pub fn new_interval(&self) -> time_tracking::NewIntervalRequestBuilder {
+        time_tracking::NewIntervalRequestBuilder::new().api(self.clone())
+    }
+    pub fn timesheet(&self) -> timesheet::TimesheetRequestBuilder {
+        timesheet::TimesheetRequestBuilder::new().api(self.clone())
+    }
+    pub fn new_case(&self) -> new_case::NewCaseRequestBuilder {
+        new_case::NewCaseRequestBuilder::new().api(self.clone())
+    }
+    pub fn edit_case(&self) -> edit_case::EditCaseRequestBuilder {
+        edit_case::EditCaseRequestBuilder::new().api(self.clone())
+    }
+    pub fn assign(&self) -> workflow::AssignRequestBuilder {
+        workflow::AssignRequestBuilder::new().api(self.clone())
+    }
+    pub fn resolve(&self) -> workflow::ResolveRequestBuilder {
+        workflow::ResolveRequestBuilder::new().api(self.clone())
+    }
+    pub fn reactivate(&self) -> workflow::TransitionRequestBuilder {
+        workflow::TransitionRequestBuilder::new(workflow::Transition::Reactivate).api(self.clone())
+    }
+    pub fn reopen(&self) -> workflow::TransitionRequestBuilder {
+        workflow::TransitionRequestBuilder::new(workflow::Transition::Reopen).api(self.clone())
+    }
+    pub fn close(&self) -> workflow::TransitionRequestBuilder {
+        workflow::TransitionRequestBuilder::new(workflow::Transition::Close).api(self.clone())
+    }
+    pub fn list_wikis(&self) -> wiki::ListWikisRequestBuilder {
+        wiki::ListWikisRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_articles(&self) -> wiki::ListArticlesRequestBuilder {
+        wiki::ListArticlesRequestBuilder::new().api(self.clone())
+    }
+    pub fn view_article(&self) -> wiki::ViewArticleRequestBuilder {
+        wiki::ViewArticleRequestBuilder::new().api(self.clone())
+    }
+    pub fn new_article(&self) -> wiki::NewArticleRequestBuilder {
+        wiki::NewArticleRequestBuilder::new().api(self.clone())
+    }
+    pub fn edit_article(&self) -> wiki::EditArticleRequestBuilder {
+        wiki::EditArticleRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_revisions(&self) -> wiki::ListRevisionsRequestBuilder {
+        wiki::ListRevisionsRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_discuss_groups(&self) -> discussion::ListDiscussGroupsRequestBuilder {
+        discussion::ListDiscussGroupsRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_discussion(&self) -> discussion::ListDiscussionRequestBuilder {
+        discussion::ListDiscussionRequestBuilder::new().api(self.clone())
+    }
+    pub fn view_topic(&self) -> discussion::ViewTopicRequestBuilder {
+        discussion::ViewTopicRequestBuilder::new().api(self.clone())
+    }
+    pub fn post_reply(&self) -> discussion::PostReplyRequestBuilder {
+        discussion::PostReplyRequestBuilder::new().api(self.clone())
+    }
+    pub fn list_filters(&self) -> filters::ListFiltersRequestBuilder {
+        filters::ListFiltersRequestBuilder::new().api(self.clone())
+    }
+    pub fn set_current_filter(&self) -> filters::SetCurrentFilterRequestBuilder {
+        filters::SetCurrentFilterRequestBuilder::new().api(self.clone())
+    }
+    pub fn subscribe(&self) -> subscriptions::SubscriptionRequestBuilder {
+        subscriptions::SubscriptionRequestBuilder::new(
+            subscriptions::SubscriptionCommand::Subscribe,
+        )
+        .api(self.clone())
+    }
+    pub fn unsubscribe(&self) -> subscriptions::SubscriptionRequestBuilder {
+        subscriptions::SubscriptionRequestBuilder::new(
+            subscriptions::SubscriptionCommand::Unsubscribe,
+        )
+        .api(self.clone())
+    }
+    /// Marks a case as read.
+    pub fn view(&self) -> subscriptions::SubscriptionRequestBuilder {
+        subscriptions::SubscriptionRequestBuilder::new(subscriptions::SubscriptionCommand::View)
+            .api(self.clone())
+    }
+    pub fn star(&self) -> subscriptions::SubscriptionRequestBuilder {
+        subscriptions::SubscriptionRequestBuilder::new(subscriptions::SubscriptionCommand::Star)
+            .api(self.clone())
+    }
+    pub fn unstar(&self) -> subscriptions::SubscriptionRequestBuilder {
+        subscriptions::SubscriptionRequestBuilder::new(subscriptions::SubscriptionCommand::Unstar)
+            .api(self.clone())
+    }
+    pub fn hierarchy(&self) -> hierarchy::HierarchyRequestBuilder {
+        hierarchy::HierarchyRequestBuilder::new().api(self.clone())
+    }
+    pub fn reply(&self) -> email::MailRequestBuilder {
+        email::MailRequestBuilder::new(email::MailCommand::Reply).api(self.clone())
+    }
+    pub fn forward(&self) -> email::MailRequestBuilder {
+        email::MailRequestBuilder::new(email::MailCommand::Forward).api(self.clone())
+    }
+    pub fn email(&self) -> email::MailRequestBuilder {
+        email::MailRequestBuilder::new(email::MailCommand::Email).api(self.clone())
+    }
+}
+
+/// Posts `request` as JSON to a command endpoint, e.g. `api/listFilters`,
+/// returning the whole response body.
+pub(crate) async fn send_command<T: serde::Serialize>(
+    api: &FogbugzApi,
+    command: &str,
+    request: &T,
+) -> Result<serde_json::Value, ResponseError> {
+    let url = reqwest::Url::parse(&api.url)?.join(command)?;
+    #[cfg(feature = "leaky-bucket")]
+    api.limiter.acquire_one().await;
+    let response = api
+        .client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .bearer_auth(&api.api_key)
+        .json(request)
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        let json: serde_json::Value = response.json().await?;
+        Ok(json)
+    } else {
+        let json: serde_json::Value = response.json().await?;
+        Err(ResponseError::FogbugzError(json))
+    }
 }
 
 #[derive(Debug, Error)]
@@ -120,4 +322,28 @@ pub enum ResponseError {
     FogbugzError(serde_json::Value),
     #[error(transparent)]
     JsonError(#[from] serde_json::Error),
+    #[error(transparent)]
+    ResolveError(#[from] refs::ResolveError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    /// A request spawned on its own task panicked or was cancelled.
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+}
+
+/// Api pointing at a placeholder instance, for tests that don't reach the
+/// server.
+#[cfg(test)]
+pub(crate) fn test_api_builder() -> FogbugzApiBuilder {
+    let builder = FogbugzApiBuilder::new()
+        .url("https://example.fogbugz.com")
+        .api_key("secret");
+    #[cfg(feature = "leaky-bucket")]
+    let builder = builder.limiter(leaky_bucket::RateLimiter::builder().build());
+    builder
+}
+
+#[cfg(test)]
+pub(crate) fn test_api() -> FogbugzApi {
+    test_api_builder().build().unwrap()
 }
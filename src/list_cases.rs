@@ -52,6 +52,7 @@ impl ListCasesRequestBuilder {
     pub fn new() -> Self {
         Self::default()
     }
+    /// Filter id, or a [`Filter`](crate::filters::Filter) returned by `listFilters`.
     pub fn filter(mut self, filter: impl AsRef<str>) -> Self {
         self.filter = Some(filter.as_ref().to_string());
         self
@@ -137,6 +138,7 @@ mod tests {
     use crate::FogbugzApiBuilder;
 
     #[tokio::test]
+    #[ignore = "requires FOGBUGZ_API_KEY and network access"]
     async fn test_list_cases_request() {
         let api_key = std::env::var("FOGBUGZ_API_KEY").unwrap();
         #[cfg(feature = "leaky-bucket")]
@@ -144,12 +146,12 @@ mod tests {
             .initial(1)
             .interval(std::time::Duration::from_secs(1))
             .build();
-        let api = FogbugzApiBuilder::new()
+        let builder = FogbugzApiBuilder::new()
             .url("https://retailic.fogbugz.com")
-            .api_key(api_key)
-            .limiter(limiter)
-            .build()
-            .unwrap();
+            .api_key(api_key);
+        #[cfg(feature = "leaky-bucket")]
+        let builder = builder.limiter(limiter);
+        let api = builder.build().unwrap();
         let request = api
             .list_cases()
             .max(1)
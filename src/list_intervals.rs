@@ -1,16 +1,17 @@
-use chrono::NaiveDateTime;
-use derivative::Derivative;
-use serde::Serialize;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{FogbugzApi, ResponseError};
+use crate::{refs::PersonRef, send_command, FogbugzApi, ResponseError};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ListIntervalsRequest {
     #[serde(rename = "ixBug", skip_serializing_if = "Option::is_none")]
     case_id: Option<u64>,
     #[serde(rename = "ixPerson", skip_serializing_if = "Option::is_none")]
-    person: Option<u64>,
+    person_id: Option<u64>,
+    #[serde(skip)]
+    person: Option<PersonRef>,
     #[serde(rename = "dtStart", skip_serializing_if = "Option::is_none")]
     start_date: Option<NaiveDateTime>,
     #[serde(rename = "dtEnd", skip_serializing_if = "Option::is_none")]
@@ -20,12 +21,10 @@ pub struct ListIntervalsRequest {
     api: FogbugzApi,
 }
 
-#[derive(Debug, Derivative)]
-#[derivative(Default)]
+#[derive(Debug, Default)]
 pub struct ListIntervalsRequestBuilder {
     case_id: Option<u64>,
-    #[derivative(Default(value = "Some(1)"))]
-    person: Option<u64>,
+    person: Option<PersonRef>,
     start_date: Option<NaiveDateTime>,
     end_date: Option<NaiveDateTime>,
     api: Option<FogbugzApi>,
@@ -37,6 +36,48 @@ pub enum ListIntervalsRequestBuilderError {
     ApiNotSpecified,
 }
 
+/// Time interval logged on a case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interval {
+    #[serde(rename = "ixInterval")]
+    pub id: u64,
+    #[serde(rename = "ixBug")]
+    pub case_id: u64,
+    #[serde(rename = "ixPerson")]
+    pub person_id: u64,
+    #[serde(rename = "dtStart")]
+    pub start: DateTime<Utc>,
+    /// `None` while the person is still working on the case.
+    #[serde(rename = "dtEnd", default, deserialize_with = "optional_date")]
+    pub end: Option<DateTime<Utc>>,
+    /// Title of the case.
+    #[serde(rename = "sTitle", default)]
+    pub title: String,
+    #[serde(rename = "fDeleted", default)]
+    pub is_deleted: bool,
+}
+
+impl Interval {
+    pub fn is_open(&self) -> bool {
+        self.end.is_none()
+    }
+    /// Length of the interval, open intervals last until `now`.
+    pub fn duration(&self, now: DateTime<Utc>) -> chrono::Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+/// Open intervals have an empty or missing `dtEnd`.
+fn optional_date<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(date) if !date.is_empty() => date.parse().map(Some).map_err(serde::de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
 impl ListIntervalsRequestBuilder {
     pub fn new() -> Self {
         Self::default()
@@ -45,8 +86,10 @@ impl ListIntervalsRequestBuilder {
         self.case_id = Some(case_id);
         self
     }
-    pub fn person(mut self, person: u64) -> Self {
-        self.person = Some(person);
+    /// Person whose intervals are listed, by id, full name or email.
+    /// FogBugz defaults to the current user.
+    pub fn person(mut self, person: impl Into<PersonRef>) -> Self {
+        self.person = Some(person.into());
         self
     }
     pub fn start_date(mut self, start_date: NaiveDateTime) -> Self {
@@ -66,9 +109,14 @@ impl ListIntervalsRequestBuilder {
             .api
             .ok_or(ListIntervalsRequestBuilderError::ApiNotSpecified)?;
         let token = api.api_key.clone();
+        let (person_id, person) = match self.person {
+            Some(PersonRef::Id(id)) => (Some(id), None),
+            person => (None, person),
+        };
         Ok(ListIntervalsRequest {
             case_id: self.case_id,
-            person: self.person,
+            person_id,
+            person,
             start_date: self.start_date,
             end_date: self.end_date,
             token,
@@ -78,14 +126,17 @@ impl ListIntervalsRequestBuilder {
 }
 
 impl ListIntervalsRequest {
-    pub async fn send(self) -> Result<serde_json::Value, ResponseError> {
-        let url = format!("{}/{}", self.api.url, "api/listIntervals");
-        let json = serde_json::to_value(&self)?;
-        dbg!(&json);
-        let response = self.api.client.post(&url).json(&self).send().await?;
-        dbg!(&response);
-        let response = response.json::<serde_json::Value>().await?;
-        Ok(response)
+    pub fn builder() -> ListIntervalsRequestBuilder {
+        ListIntervalsRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Interval>, ResponseError> {
+        let mut request = self.clone();
+        if let Some(person) = request.person.take() {
+            request.person_id = Some(person.resolve(&self.api).await?);
+        }
+        let mut json = send_command(&self.api, "api/listIntervals", &request).await?;
+        let intervals = serde_json::from_value(json["data"]["intervals"].take())?;
+        Ok(intervals)
     }
 }
 
@@ -95,6 +146,7 @@ mod tests {
     use crate::FogbugzApiBuilder;
 
     #[tokio::test]
+    #[ignore = "requires FOGBUGZ_API_KEY and network access"]
     async fn test_list_intervals_request() {
         let api_key = std::env::var("FOGBUGZ_API_KEY").unwrap();
         #[cfg(feature = "leaky-bucket")]
@@ -103,12 +155,12 @@ mod tests {
             .interval(std::time::Duration::from_secs(1))
             .build();
 
-        let api = FogbugzApiBuilder::new()
+        let builder = FogbugzApiBuilder::new()
             .url("https://retailic.fogbugz.com")
-            .api_key(api_key)
-            .limiter(limiter)
-            .build()
-            .unwrap();
+            .api_key(api_key);
+        #[cfg(feature = "leaky-bucket")]
+        let builder = builder.limiter(limiter);
+        let api = builder.build().unwrap();
 
         let start_date =
             NaiveDateTime::parse_from_str("2024-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
@@ -123,7 +175,42 @@ mod tests {
             .build()
             .unwrap();
 
-        let res = request.send().await;
-        dbg!(res);
+        let intervals = request.send().await.unwrap();
+        assert!(intervals
+            .iter()
+            .all(|interval| interval.end.is_none_or(|end| end >= interval.start)));
+    }
+
+    #[test]
+    fn test_deserialize_interval() {
+        let interval: Interval = serde_json::from_value(serde_json::json!({
+            "ixInterval": 5,
+            "ixBug": 42,
+            "ixPerson": 7,
+            "dtStart": "2024-01-08T09:00:00Z",
+            "dtEnd": "",
+            "sTitle": "Printer on fire",
+            "fDeleted": false,
+        }))
+        .unwrap();
+        assert!(interval.is_open());
+        let now = "2024-01-08T10:00:00Z".parse().unwrap();
+        assert_eq!(interval.duration(now), chrono::Duration::hours(1));
+    }
+
+    #[test]
+    fn test_list_intervals_request_serialization() {
+        let api = crate::test_api();
+
+        let request = api.list_intervals().case_id(42).build().unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "ixBug": 42, "token": "secret" })
+        );
+        let request = api.list_intervals().person(7).build().unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "ixPerson": 7, "token": "secret" })
+        );
     }
 }
@@ -0,0 +1,717 @@
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{send_command, FogbugzApi, ResponseError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    #[serde(rename = "ixProject")]
+    pub id: u64,
+    #[serde(rename = "sProject")]
+    pub name: String,
+    #[serde(rename = "ixPersonOwner", default)]
+    pub owner_id: Option<u64>,
+    #[serde(rename = "sPersonOwner", default)]
+    pub owner: Option<String>,
+    #[serde(rename = "sEmail", default)]
+    pub email: Option<String>,
+    #[serde(rename = "sPhone", default)]
+    pub phone: Option<String>,
+    #[serde(rename = "fInbox", default)]
+    pub is_inbox: bool,
+    #[serde(rename = "ixWorkflow", default)]
+    pub workflow_id: Option<u64>,
+    #[serde(rename = "fDeleted", default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Area {
+    #[serde(rename = "ixArea")]
+    pub id: u64,
+    #[serde(rename = "sArea")]
+    pub name: String,
+    #[serde(rename = "ixProject")]
+    pub project_id: u64,
+    #[serde(rename = "sProject", default)]
+    pub project: Option<String>,
+    #[serde(rename = "ixPersonOwner", default)]
+    pub owner_id: Option<u64>,
+    #[serde(rename = "sPersonOwner", default)]
+    pub owner: Option<String>,
+    /// 0 for normal areas, 1 for the area receiving not-spam and 2 for
+    /// the area receiving undecided mail.
+    #[serde(rename = "nType", default)]
+    pub area_type: u8,
+    /// Number of documents used to train the spam filter.
+    #[serde(rename = "cDoc", default)]
+    pub doc_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Person {
+    #[serde(rename = "ixPerson")]
+    pub id: u64,
+    #[serde(rename = "sFullName")]
+    pub full_name: String,
+    #[serde(rename = "sEmail", default)]
+    pub email: Option<String>,
+    #[serde(rename = "sPhone", default)]
+    pub phone: Option<String>,
+    #[serde(rename = "fAdministrator", default)]
+    pub is_administrator: bool,
+    #[serde(rename = "fCommunity", default)]
+    pub is_community: bool,
+    #[serde(rename = "fVirtual", default)]
+    pub is_virtual: bool,
+    #[serde(rename = "fDeleted", default)]
+    pub is_deleted: bool,
+    #[serde(rename = "sTimeZoneKey", default)]
+    pub time_zone: Option<String>,
+    #[serde(rename = "dtLastActivity", default)]
+    pub last_activity: Option<DateTime<Utc>>,
+}
+
+/// Milestone, called a fix for in the FogBugz API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Milestone {
+    #[serde(rename = "ixFixFor")]
+    pub id: u64,
+    #[serde(rename = "sFixFor")]
+    pub name: String,
+    /// `None` for global milestones.
+    #[serde(rename = "ixProject", default)]
+    pub project_id: Option<u64>,
+    #[serde(rename = "sProject", default)]
+    pub project: Option<String>,
+    #[serde(rename = "dt", default)]
+    pub date: Option<DateTime<Utc>>,
+    #[serde(rename = "dtStart", default)]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(rename = "sStartNote", default)]
+    pub start_note: Option<String>,
+    #[serde(rename = "fDeleted", default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusInfo {
+    #[serde(rename = "ixStatus")]
+    pub id: u64,
+    #[serde(rename = "sStatus")]
+    pub name: String,
+    #[serde(rename = "ixCategory")]
+    pub category_id: u64,
+    #[serde(rename = "fWorkDone", default)]
+    pub is_work_done: bool,
+    #[serde(rename = "fResolved", default)]
+    pub is_resolved: bool,
+    #[serde(rename = "fDuplicate", default)]
+    pub is_duplicate: bool,
+    #[serde(rename = "fDeleted", default)]
+    pub is_deleted: bool,
+    #[serde(rename = "fReactivate", default)]
+    pub is_reactivate: bool,
+    #[serde(rename = "iOrder", default)]
+    pub order: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryInfo {
+    #[serde(rename = "ixCategory")]
+    pub id: u64,
+    #[serde(rename = "sCategory")]
+    pub name: String,
+    #[serde(rename = "sPlural", default)]
+    pub plural: Option<String>,
+    /// Status a case of this category gets when resolved by default.
+    #[serde(rename = "ixStatusDefault", default)]
+    pub default_status_id: Option<u64>,
+    /// Status a case of this category gets when opened or reactivated.
+    #[serde(rename = "ixStatusDefaultActive", default)]
+    pub default_active_status_id: Option<u64>,
+    #[serde(rename = "fIsScheduleItem", default)]
+    pub is_schedule_item: bool,
+    #[serde(rename = "fDeleted", default)]
+    pub is_deleted: bool,
+    #[serde(rename = "iOrder", default)]
+    pub order: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorityInfo {
+    #[serde(rename = "ixPriority")]
+    pub id: u64,
+    #[serde(rename = "sPriority")]
+    pub name: String,
+    #[serde(rename = "fDefault", default)]
+    pub is_default: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListProjectsRequest {
+    #[serde(rename = "fWrite", skip_serializing_if = "Option::is_none")]
+    writable: Option<bool>,
+    #[serde(rename = "ixProject", skip_serializing_if = "Option::is_none")]
+    project_id: Option<u64>,
+    #[serde(rename = "fIncludeDeleted", skip_serializing_if = "Option::is_none")]
+    include_deleted: Option<bool>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListProjectsRequestBuilder {
+    writable: Option<bool>,
+    project_id: Option<u64>,
+    include_deleted: Option<bool>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListProjectsRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListProjectsRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Only lists projects the user can write to.
+    pub fn writable(mut self, writable: bool) -> Self {
+        self.writable = Some(writable);
+        self
+    }
+    /// Always includes this project, even if it is deleted.
+    pub fn project_id(mut self, project_id: u64) -> Self {
+        self.project_id = Some(project_id);
+        self
+    }
+    pub fn include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = Some(include_deleted);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListProjectsRequest, ListProjectsRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListProjectsRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListProjectsRequest {
+            writable: self.writable,
+            project_id: self.project_id,
+            include_deleted: self.include_deleted,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListProjectsRequest {
+    pub fn builder() -> ListProjectsRequestBuilder {
+        ListProjectsRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Project>, ResponseError> {
+        send_list_command(&self.api, "api/listProjects", "projects", self).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListAreasRequest {
+    #[serde(rename = "fWrite", skip_serializing_if = "Option::is_none")]
+    writable: Option<bool>,
+    #[serde(rename = "ixProject", skip_serializing_if = "Option::is_none")]
+    project_id: Option<u64>,
+    #[serde(rename = "ixArea", skip_serializing_if = "Option::is_none")]
+    area_id: Option<u64>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListAreasRequestBuilder {
+    writable: Option<bool>,
+    project_id: Option<u64>,
+    area_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListAreasRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListAreasRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Only lists areas the user can write to.
+    pub fn writable(mut self, writable: bool) -> Self {
+        self.writable = Some(writable);
+        self
+    }
+    /// Only lists areas of this project.
+    pub fn project_id(mut self, project_id: u64) -> Self {
+        self.project_id = Some(project_id);
+        self
+    }
+    /// Always includes this area, even if it is deleted.
+    pub fn area_id(mut self, area_id: u64) -> Self {
+        self.area_id = Some(area_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListAreasRequest, ListAreasRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListAreasRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListAreasRequest {
+            writable: self.writable,
+            project_id: self.project_id,
+            area_id: self.area_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListAreasRequest {
+    pub fn builder() -> ListAreasRequestBuilder {
+        ListAreasRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Area>, ResponseError> {
+        send_list_command(&self.api, "api/listAreas", "areas", self).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListPeopleRequest {
+    #[serde(rename = "fIncludeActive", skip_serializing_if = "Option::is_none")]
+    include_active: Option<bool>,
+    #[serde(rename = "fIncludeNormal", skip_serializing_if = "Option::is_none")]
+    include_normal: Option<bool>,
+    #[serde(rename = "fIncludeDeleted", skip_serializing_if = "Option::is_none")]
+    include_deleted: Option<bool>,
+    #[serde(rename = "fIncludeCommunity", skip_serializing_if = "Option::is_none")]
+    include_community: Option<bool>,
+    #[serde(rename = "fIncludeVirtual", skip_serializing_if = "Option::is_none")]
+    include_virtual: Option<bool>,
+    #[serde(rename = "ixPerson", skip_serializing_if = "Option::is_none")]
+    person_id: Option<u64>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListPeopleRequestBuilder {
+    include_active: Option<bool>,
+    include_normal: Option<bool>,
+    include_deleted: Option<bool>,
+    include_community: Option<bool>,
+    include_virtual: Option<bool>,
+    person_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListPeopleRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListPeopleRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn include_active(mut self, include_active: bool) -> Self {
+        self.include_active = Some(include_active);
+        self
+    }
+    pub fn include_normal(mut self, include_normal: bool) -> Self {
+        self.include_normal = Some(include_normal);
+        self
+    }
+    pub fn include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = Some(include_deleted);
+        self
+    }
+    pub fn include_community(mut self, include_community: bool) -> Self {
+        self.include_community = Some(include_community);
+        self
+    }
+    pub fn include_virtual(mut self, include_virtual: bool) -> Self {
+        self.include_virtual = Some(include_virtual);
+        self
+    }
+    /// Always includes this person, even if they are deleted.
+    pub fn person_id(mut self, person_id: u64) -> Self {
+        self.person_id = Some(person_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListPeopleRequest, ListPeopleRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListPeopleRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListPeopleRequest {
+            include_active: self.include_active,
+            include_normal: self.include_normal,
+            include_deleted: self.include_deleted,
+            include_community: self.include_community,
+            include_virtual: self.include_virtual,
+            person_id: self.person_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListPeopleRequest {
+    pub fn builder() -> ListPeopleRequestBuilder {
+        ListPeopleRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Person>, ResponseError> {
+        send_list_command(&self.api, "api/listPeople", "people", self).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListMilestonesRequest {
+    #[serde(rename = "ixProject", skip_serializing_if = "Option::is_none")]
+    project_id: Option<u64>,
+    #[serde(rename = "ixFixFor", skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<u64>,
+    #[serde(rename = "fIncludeDeleted", skip_serializing_if = "Option::is_none")]
+    include_deleted: Option<bool>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListMilestonesRequestBuilder {
+    project_id: Option<u64>,
+    milestone_id: Option<u64>,
+    include_deleted: Option<bool>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListMilestonesRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListMilestonesRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Only lists milestones of this project and global ones.
+    pub fn project_id(mut self, project_id: u64) -> Self {
+        self.project_id = Some(project_id);
+        self
+    }
+    /// Always includes this milestone, even if it is deleted.
+    pub fn milestone_id(mut self, milestone_id: u64) -> Self {
+        self.milestone_id = Some(milestone_id);
+        self
+    }
+    pub fn include_deleted(mut self, include_deleted: bool) -> Self {
+        self.include_deleted = Some(include_deleted);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListMilestonesRequest, ListMilestonesRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListMilestonesRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListMilestonesRequest {
+            project_id: self.project_id,
+            milestone_id: self.milestone_id,
+            include_deleted: self.include_deleted,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListMilestonesRequest {
+    pub fn builder() -> ListMilestonesRequestBuilder {
+        ListMilestonesRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Milestone>, ResponseError> {
+        send_list_command(&self.api, "api/listFixFors", "fixfors", self).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListStatusesRequest {
+    #[serde(rename = "ixCategory", skip_serializing_if = "Option::is_none")]
+    category_id: Option<u64>,
+    #[serde(rename = "fResolved", skip_serializing_if = "Option::is_none")]
+    resolved: Option<bool>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListStatusesRequestBuilder {
+    category_id: Option<u64>,
+    resolved: Option<bool>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListStatusesRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListStatusesRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Only lists statuses of this category.
+    pub fn category_id(mut self, category_id: u64) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+    /// Only lists resolved statuses.
+    pub fn resolved(mut self, resolved: bool) -> Self {
+        self.resolved = Some(resolved);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListStatusesRequest, ListStatusesRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListStatusesRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListStatusesRequest {
+            category_id: self.category_id,
+            resolved: self.resolved,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListStatusesRequest {
+    pub fn builder() -> ListStatusesRequestBuilder {
+        ListStatusesRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<StatusInfo>, ResponseError> {
+        send_list_command(&self.api, "api/listStatuses", "statuses", self).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListCategoriesRequest {
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListCategoriesRequestBuilder {
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListCategoriesRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListCategoriesRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListCategoriesRequest, ListCategoriesRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListCategoriesRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListCategoriesRequest {
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListCategoriesRequest {
+    pub fn builder() -> ListCategoriesRequestBuilder {
+        ListCategoriesRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<CategoryInfo>, ResponseError> {
+        send_list_command(&self.api, "api/listCategories", "categories", self).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListPrioritiesRequest {
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListPrioritiesRequestBuilder {
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListPrioritiesRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListPrioritiesRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListPrioritiesRequest, ListPrioritiesRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListPrioritiesRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListPrioritiesRequest {
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListPrioritiesRequest {
+    pub fn builder() -> ListPrioritiesRequestBuilder {
+        ListPrioritiesRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<PriorityInfo>, ResponseError> {
+        send_list_command(&self.api, "api/listPriorities", "priorities", self).await
+    }
+}
+
+/// Unfiltered listing as cached by [`MetadataCache`](crate::cache::MetadataCache),
+/// sent without going through a request builder.
+pub(crate) async fn list_all<R: DeserializeOwned>(
+    api: &FogbugzApi,
+    command: &str,
+    key: &str,
+) -> Result<Vec<R>, ResponseError> {
+    #[derive(Serialize)]
+    struct ListRequest<'a> {
+        token: &'a str,
+    }
+
+    let request = ListRequest {
+        token: &api.api_key,
+    };
+    send_list_command(api, command, key, &request).await
+}
+
+async fn send_list_command<T: Serialize, R: DeserializeOwned>(
+    api: &FogbugzApi,
+    command: &str,
+    key: &str,
+    request: &T,
+) -> Result<Vec<R>, ResponseError> {
+    let mut json = send_command(api, command, request).await?;
+    let items = serde_json::from_value::<Vec<R>>(json["data"][key].take())?;
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_api;
+
+    #[test]
+    fn test_list_request_serialization() {
+        let request = test_api()
+            .list_people()
+            .include_virtual(true)
+            .person_id(7)
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "fIncludeVirtual": true,
+                "ixPerson": 7,
+                "token": "secret",
+            })
+        );
+        let request = test_api().list_priorities().build().unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "token": "secret" })
+        );
+    }
+
+    #[test]
+    fn test_deserialize_metadata() {
+        let project: Project = serde_json::from_value(serde_json::json!({
+            "ixProject": 2,
+            "sProject": "Inbox",
+            "ixPersonOwner": 1,
+            "sPersonOwner": "Administrator",
+            "fInbox": true,
+        }))
+        .unwrap();
+        assert_eq!(project.id, 2);
+        assert!(project.is_inbox);
+        assert!(!project.is_deleted);
+
+        let milestone: Milestone = serde_json::from_value(serde_json::json!({
+            "ixFixFor": 1,
+            "sFixFor": "Undecided",
+            "fDeleted": false,
+            "dt": null,
+            "ixProject": null,
+        }))
+        .unwrap();
+        assert!(milestone.project_id.is_none());
+        assert!(milestone.date.is_none());
+
+        let status: StatusInfo = serde_json::from_value(serde_json::json!({
+            "ixStatus": 34,
+            "sStatus": "Resolved (Completed)",
+            "ixCategory": 6,
+            "fWorkDone": true,
+            "fResolved": true,
+            "fDuplicate": false,
+            "fDeleted": false,
+            "fReactivate": false,
+            "iOrder": 0,
+        }))
+        .unwrap();
+        assert!(status.is_work_done && status.is_resolved);
+        assert_eq!(status.category_id, 6);
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    attachments::{self, FileUpload},
+    enums::{Category, Priority},
+    refs::{PersonRef, ProjectRef},
+    FogbugzApi, ResponseError,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewCaseRequest {
+    #[serde(rename = "sTitle")]
+    title: String,
+    #[serde(rename = "ixProject", skip_serializing_if = "Option::is_none")]
+    project_id: Option<u64>,
+    #[serde(skip)]
+    project: Option<ProjectRef>,
+    #[serde(rename = "ixArea", skip_serializing_if = "Option::is_none")]
+    area_id: Option<u64>,
+    #[serde(rename = "sArea", skip_serializing_if = "Option::is_none")]
+    area: Option<String>,
+    #[serde(rename = "ixCategory", skip_serializing_if = "Option::is_none")]
+    category: Option<Category>,
+    #[serde(rename = "ixPriority", skip_serializing_if = "Option::is_none")]
+    priority: Option<Priority>,
+    #[serde(rename = "ixPersonAssignedTo", skip_serializing_if = "Option::is_none")]
+    assigned_to_id: Option<u64>,
+    #[serde(skip)]
+    assigned_to: Option<PersonRef>,
+    #[serde(rename = "ixFixFor", skip_serializing_if = "Option::is_none")]
+    milestone_id: Option<u64>,
+    #[serde(rename = "sFixFor", skip_serializing_if = "Option::is_none")]
+    milestone: Option<String>,
+    #[serde(rename = "sTags", skip_serializing_if = "Option::is_none")]
+    tags: Option<String>,
+    #[serde(flatten)]
+    custom_fields: BTreeMap<String, String>,
+    #[serde(rename = "sEvent", skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    #[serde(skip)]
+    attachments: Vec<FileUpload>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct NewCaseRequestBuilder {
+    title: Option<String>,
+    project: Option<ProjectRef>,
+    area_id: Option<u64>,
+    area: Option<String>,
+    category: Option<Category>,
+    priority: Option<Priority>,
+    assigned_to: Option<PersonRef>,
+    milestone_id: Option<u64>,
+    milestone: Option<String>,
+    tags: Option<Vec<String>>,
+    custom_fields: BTreeMap<String, String>,
+    event: Option<String>,
+    attachments: Vec<FileUpload>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum NewCaseRequestBuilderError {
+    #[error("Title is not specified")]
+    TitleNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewCase {
+    #[serde(rename = "ixBug")]
+    pub case_id: u64,
+    #[serde(default)]
+    pub operations: Vec<String>,
+}
+
+impl NewCaseRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn title(mut self, title: impl AsRef<str>) -> Self {
+        self.title = Some(title.as_ref().to_string());
+        self
+    }
+    pub fn project_id(mut self, project_id: u64) -> Self {
+        self.project = Some(ProjectRef::Id(project_id));
+        self
+    }
+    /// Project by id or name, names are resolved when sending.
+    pub fn project(mut self, project: impl Into<ProjectRef>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+    pub fn area_id(mut self, area_id: u64) -> Self {
+        self.area_id = Some(area_id);
+        self
+    }
+    pub fn area(mut self, area: impl AsRef<str>) -> Self {
+        self.area = Some(area.as_ref().to_string());
+        self
+    }
+    pub fn category(mut self, category: impl Into<Category>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+    pub fn priority(mut self, priority: impl Into<Priority>) -> Self {
+        self.priority = Some(priority.into());
+        self
+    }
+    pub fn assigned_to_id(mut self, person_id: u64) -> Self {
+        self.assigned_to = Some(PersonRef::Id(person_id));
+        self
+    }
+    /// Assignee by id, full name or email, names are resolved when sending.
+    pub fn assigned_to(mut self, person: impl Into<PersonRef>) -> Self {
+        self.assigned_to = Some(person.into());
+        self
+    }
+    pub fn milestone_id(mut self, milestone_id: u64) -> Self {
+        self.milestone_id = Some(milestone_id);
+        self
+    }
+    pub fn milestone(mut self, milestone: impl AsRef<str>) -> Self {
+        self.milestone = Some(milestone.as_ref().to_string());
+        self
+    }
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.tags = Some(tags.iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn add_tag(mut self, tag: impl AsRef<str>) -> Self {
+        if let Some(tags) = &mut self.tags {
+            tags.push(tag.as_ref().to_string())
+        } else {
+            self.tags = Some(vec![tag.as_ref().to_string()]);
+        }
+        self
+    }
+    /// Sets a custom field, given as a [`CustomField`](crate::custom_fields::CustomField)
+    /// or by its API column name, e.g. `plugin_customfields_at_fogcreek_com_customerx12`.
+    pub fn custom_field(mut self, field: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.custom_fields
+            .insert(field.as_ref().to_string(), value.as_ref().to_string());
+        self
+    }
+    pub fn event(mut self, event: impl AsRef<str>) -> Self {
+        self.event = Some(event.as_ref().to_string());
+        self
+    }
+    /// Uploads the file with the command, switching the request to multipart.
+    pub fn attachment(mut self, file: FileUpload) -> Self {
+        self.attachments.push(file);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<NewCaseRequest, NewCaseRequestBuilderError> {
+        let title = self
+            .title
+            .ok_or(NewCaseRequestBuilderError::TitleNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(NewCaseRequestBuilderError::ApiNotSpecified)?;
+        let (project_id, project) = match self.project {
+            Some(ProjectRef::Id(id)) => (Some(id), None),
+            project => (None, project),
+        };
+        let (assigned_to_id, assigned_to) = match self.assigned_to {
+            Some(PersonRef::Id(id)) => (Some(id), None),
+            person => (None, person),
+        };
+        Ok(NewCaseRequest {
+            title,
+            project_id,
+            project,
+            area_id: self.area_id,
+            area: self.area,
+            category: self.category,
+            priority: self.priority,
+            assigned_to_id,
+            assigned_to,
+            milestone_id: self.milestone_id,
+            milestone: self.milestone,
+            tags: self.tags.map(|tags| tags.join(",")),
+            custom_fields: self.custom_fields,
+            event: self.event,
+            attachments: self.attachments,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl NewCaseRequest {
+    pub fn builder() -> NewCaseRequestBuilder {
+        NewCaseRequestBuilder::new()
+    }
+    /// Copy of the request with the project and assignee names replaced by ids.
+    async fn resolve(&self) -> Result<NewCaseRequest, ResponseError> {
+        let mut request = self.clone();
+        if let Some(project) = request.project.take() {
+            request.project_id = Some(project.resolve(&self.api).await?);
+        }
+        if let Some(person) = request.assigned_to.take() {
+            request.assigned_to_id = Some(person.resolve(&self.api).await?);
+        }
+        Ok(request)
+    }
+    pub async fn send(&self) -> Result<NewCase, ResponseError> {
+        let request = self.resolve().await?;
+        let mut json =
+            attachments::send_with_files(&self.api, "api/new", &request, &request.attachments)
+                .await?;
+        let new_case = serde_json::from_value(json["data"]["case"].take())?;
+        Ok(new_case)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cache::{MetadataCache, DEFAULT_TTL},
+        test_api_builder,
+    };
+
+    const SNAPSHOT: &str = r#"{"projects": [{"ixProject": 2, "sProject": "Inbox"}]}"#;
+
+    fn api() -> FogbugzApi {
+        test_api_builder()
+            .cache(MetadataCache::from_json(DEFAULT_TTL, SNAPSHOT).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_new_case_request_requires_title() {
+        let res = NewCaseRequestBuilder::new().api(api()).build();
+        assert!(matches!(
+            res,
+            Err(NewCaseRequestBuilderError::TitleNotSpecified)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_new_case_request_serialization() {
+        let request = api()
+            .new_case()
+            .title("Printer on fire")
+            .project("Inbox")
+            .category(1)
+            .priority(1)
+            .assigned_to_id(7)
+            .add_tag("hardware")
+            .add_tag("urgent")
+            .custom_field("plugin_customfields_at_fogcreek_com_customerx12", "ACME")
+            .event("Smoke everywhere")
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(request.resolve().await.unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "sTitle": "Printer on fire",
+                "ixProject": 2,
+                "ixCategory": 1,
+                "ixPriority": 1,
+                "ixPersonAssignedTo": 7,
+                "sTags": "hardware,urgent",
+                "plugin_customfields_at_fogcreek_com_customerx12": "ACME",
+                "sEvent": "Smoke everywhere",
+                "token": "secret",
+            })
+        );
+    }
+}
@@ -1,50 +1,326 @@
 use core::fmt;
+use std::{ops::Not, str::FromStr};
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::date::Date;
+use crate::{custom_fields::CustomField, date::Date, enums::Priority, refs::PersonRef};
 
-#[derive(Debug)]
+/// Value of the `status:` search axis.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum StatusFilter {
+    Open,
+    Closed,
+    Active,
+    Resolved,
+    /// A status by its full name, e.g. `Resolved (Fixed)`.
+    Named(String),
+}
+
+impl fmt::Display for StatusFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatusFilter::Open => write!(f, "open"),
+            StatusFilter::Closed => write!(f, "closed"),
+            StatusFilter::Active => write!(f, "active"),
+            StatusFilter::Resolved => write!(f, "resolved"),
+            StatusFilter::Named(name) => write!(f, "{}", quote(name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Param {
     CaseId(u64),
-    AssignedTo(String),
+    /// Person ids are rendered as is, names and email addresses quoted when
+    /// needed.
+    AssignedTo(PersonRef),
     FromEmail(String),
     OpenedDate(Date),
     ClosedDate(Date),
+    Project(String),
+    Area(String),
+    Milestone(String),
+    Status(StatusFilter),
+    /// Category by name, e.g. `Bug`.
+    Category(String),
+    Priority(Priority),
+    Tag(String),
+    EditedDate(Date),
+    ResolvedDate(Date),
+    DueDate(Date),
+    LastEditedDate(Date),
+    OpenedBy(String),
+    ResolvedBy(String),
+    Correspondent(String),
+    Parent(u64),
+    Children(u64),
+    StarredBy(String),
+    ViewedBy(String),
+    SubscribedBy(String),
+    Text(String),
+    /// Any other `axis:value` term, e.g. `outline:42`.
+    Other(String, String),
 }
 
-#[derive(Debug, Deserialize)]
-pub struct Query {
-    pub case_id: Option<u64>,
-    pub assigned_to: Option<String>,
-    pub from_email: Option<String>,
-    pub opened_date: Option<Date>,
-    pub closed_date: Option<Date>,
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Param::CaseId(case_id) => write!(f, "ixBug:{}", case_id),
+            Param::AssignedTo(PersonRef::Id(id)) => write!(f, "assignedTo:{}", id),
+            Param::AssignedTo(PersonRef::FullName(name) | PersonRef::Email(name)) => {
+                write!(f, "assignedTo:{}", quote(name))
+            }
+            Param::FromEmail(from_email) => write!(f, "from:{}", quote(from_email)),
+            Param::OpenedDate(opened_date) => write!(f, "opened:\"{}\"", opened_date),
+            Param::ClosedDate(closed_date) => write!(f, "closed:\"{}\"", closed_date),
+            Param::Project(project) => write!(f, "project:{}", quote(project)),
+            Param::Area(area) => write!(f, "area:{}", quote(area)),
+            Param::Milestone(milestone) => write!(f, "milestone:{}", quote(milestone)),
+            Param::Status(status) => write!(f, "status:{}", status),
+            Param::Category(category) => write!(f, "category:{}", quote(category)),
+            Param::Priority(priority) => write!(f, "priority:{}", priority.id()),
+            Param::Tag(tag) => write!(f, "tag:{}", quote(tag)),
+            Param::EditedDate(edited_date) => write!(f, "edited:\"{}\"", edited_date),
+            Param::ResolvedDate(resolved_date) => write!(f, "resolved:\"{}\"", resolved_date),
+            Param::DueDate(due_date) => write!(f, "due:\"{}\"", due_date),
+            Param::LastEditedDate(last_edited_date) => {
+                write!(f, "lastEdited:\"{}\"", last_edited_date)
+            }
+            Param::OpenedBy(opened_by) => write!(f, "openedBy:{}", quote(opened_by)),
+            Param::ResolvedBy(resolved_by) => write!(f, "resolvedBy:{}", quote(resolved_by)),
+            Param::Correspondent(correspondent) => {
+                write!(f, "correspondent:{}", quote(correspondent))
+            }
+            Param::Parent(parent) => write!(f, "parent:{}", parent),
+            Param::Children(children) => write!(f, "children:{}", children),
+            Param::StarredBy(starred_by) => write!(f, "starredBy:{}", quote(starred_by)),
+            Param::ViewedBy(viewed_by) => write!(f, "viewedBy:{}", quote(viewed_by)),
+            Param::SubscribedBy(subscribed_by) => {
+                write!(f, "subscribedBy:{}", quote(subscribed_by))
+            }
+            Param::Text(text) => write!(f, "{}", quote(text)),
+            Param::Other(axis, value) => write!(f, "{}:{}", quote(axis), quote(value)),
+        }
+    }
 }
 
-#[derive(Debug, Default)]
-pub struct QueryBuilder(pub Vec<Param>);
+/// Boolean search expression.
+///
+/// Terms of an `And` are separated by spaces, terms of an `Or` by `OR` and a
+/// negated term is prefixed with `-`. Nested `And`/`Or` terms are put in
+/// parentheses when rendered, `Group` adds parentheses explicitly. Parsing
+/// never yields a `Group`, parentheses only nest the expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Param(Param),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Group(Box<Expr>),
+}
 
-impl fmt::Display for Query {
+impl Expr {
+    /// Unwraps single-term `And`/`Or` nodes, which render without any trace
+    /// of the wrapper.
+    fn simplified(self) -> Expr {
+        match self {
+            Expr::And(mut exprs) | Expr::Or(mut exprs) if exprs.len() == 1 => {
+                exprs.remove(0).simplified()
+            }
+            expr => expr,
+        }
+    }
+    fn is_compound(&self) -> bool {
+        match self {
+            Expr::And(exprs) | Expr::Or(exprs) => match exprs.as_slice() {
+                [single] => single.is_compound(),
+                _ => exprs.len() > 1,
+            },
+            _ => false,
+        }
+    }
+    fn fmt_term(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_compound() {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut parts = Vec::new();
-        if let Some(case_id) = self.case_id {
-            parts.push(format!("ixBug:{}", case_id));
+        match self {
+            Expr::Param(param) => write!(f, "{}", param),
+            Expr::And(exprs) | Expr::Or(exprs) => {
+                let separator = if matches!(self, Expr::And(_)) {
+                    " "
+                } else {
+                    " OR "
+                };
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", separator)?;
+                    }
+                    if exprs.len() > 1 {
+                        expr.fmt_term(f)?;
+                    } else {
+                        write!(f, "{}", expr)?;
+                    }
+                }
+                Ok(())
+            }
+            Expr::Not(expr) => {
+                let mut expr = expr.as_ref();
+                while let Expr::And(exprs) | Expr::Or(exprs) = expr {
+                    match exprs.as_slice() {
+                        [single] => expr = single,
+                        _ => break,
+                    }
+                }
+                match expr {
+                    Expr::And(_) | Expr::Or(_) => write!(f, "-({})", expr),
+                    _ => write!(f, "-{}", expr),
+                }
+            }
+            Expr::Group(expr) => write!(f, "({})", expr),
         }
-        if let Some(assigned_to) = &self.assigned_to {
-            parts.push(format!("assignedTo:{}", assigned_to));
+    }
+}
+
+impl From<Param> for Expr {
+    fn from(param: Param) -> Self {
+        Expr::Param(param)
+    }
+}
+
+/// Field a search can be ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum SortField {
+    #[strum(serialize = "Case")]
+    CaseId,
+    Title,
+    Project,
+    Area,
+    Category,
+    Priority,
+    Status,
+    AssignedTo,
+    Milestone,
+    Opened,
+    Resolved,
+    Closed,
+    #[strum(serialize = "Due")]
+    DueDate,
+    LastEdited,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A single `orderBy:` key, descending keys are rendered with a `-` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderBy {
+    pub field: SortField,
+    pub direction: SortDirection,
+}
+
+impl OrderBy {
+    pub fn asc(field: SortField) -> Self {
+        Self {
+            field,
+            direction: SortDirection::Ascending,
         }
-        if let Some(from_email) = &self.from_email {
-            parts.push(format!("from:{}", from_email));
+    }
+    pub fn desc(field: SortField) -> Self {
+        Self {
+            field,
+            direction: SortDirection::Descending,
         }
-        if let Some(opened_date) = &self.opened_date {
-            parts.push(format!("opened:\"{}\"", opened_date));
+    }
+}
+
+impl fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.direction {
+            SortDirection::Ascending => write!(f, "orderBy:{}", self.field),
+            SortDirection::Descending => {
+                write!(f, "orderBy:{}", quote(&format!("-{}", self.field)))
+            }
         }
-        if let Some(closed_date) = &self.closed_date {
-            parts.push(format!("closed:\"{}\"", closed_date));
+    }
+}
+
+impl FromStr for OrderBy {
+    type Err = strum::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(field) => Ok(OrderBy::desc(field.parse()?)),
+            None => Ok(OrderBy::asc(s.parse()?)),
+        }
+    }
+}
+
+/// A search expression with its ordering and result cap.
+///
+/// Ordering keys are rendered after the expression, in order of precedence.
+/// `max` is not part of the search syntax, it is picked up by the search
+/// request instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub expr: Expr,
+    pub order_by: Vec<OrderBy>,
+    pub max: Option<u32>,
+}
+
+#[derive(Debug, Default)]
+pub struct QueryBuilder {
+    exprs: Vec<Expr>,
+    order_by: Vec<OrderBy>,
+    max: Option<u32>,
+}
+
+/// Quotes values FogBugz would otherwise split into several search terms or
+/// read as an operator, escaping `"` and `\` inside the quotes.
+fn quote(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.starts_with('-')
+        || value.eq_ignore_ascii_case("or")
+        || value.eq_ignore_ascii_case("and")
+        || value.contains(|c: char| c.is_whitespace() || ":\"()\\".contains(c));
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut expr = self.expr.to_string();
+        for order_by in &self.order_by {
+            if !expr.is_empty() {
+                expr.push(' ');
+            }
+            expr.push_str(&order_by.to_string());
+        }
+        write!(f, "{}", expr)
+    }
+}
+
+impl From<Expr> for Query {
+    fn from(expr: Expr) -> Self {
+        Query {
+            expr,
+            order_by: Vec::new(),
+            max: None,
         }
-        let query = parts.join("&");
-        write!(f, "{}", query)
     }
 }
 
@@ -56,52 +332,193 @@ impl Serialize for Query {
 
 impl QueryBuilder {
     pub fn new() -> Self {
-        Self(Vec::new())
+        Self::default()
     }
     pub fn add_param(mut self, param: Param) -> Self {
-        self.0.push(param);
+        self.exprs.push(Expr::Param(param));
         self
     }
     pub fn case_id(mut self, case_id: u64) -> Self {
-        self.0.push(Param::CaseId(case_id));
+        self.exprs.push(Expr::Param(Param::CaseId(case_id)));
         self
     }
-    pub fn assigned_to(mut self, assigned_to: impl AsRef<str>) -> Self {
-        self.0
-            .push(Param::AssignedTo(assigned_to.as_ref().to_string()));
+    /// Person id, full name or email address.
+    pub fn assigned_to(mut self, person: impl Into<PersonRef>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::AssignedTo(person.into())));
         self
     }
     pub fn from_email(mut self, from_email: impl AsRef<str>) -> Self {
-        self.0
-            .push(Param::FromEmail(from_email.as_ref().to_string()));
+        self.exprs.push(Expr::Param(Param::FromEmail(
+            from_email.as_ref().to_string(),
+        )));
         self
     }
     pub fn opened_date(mut self, opened_date: impl Into<Date>) -> Self {
-        self.0.push(Param::OpenedDate(opened_date.into()));
+        self.exprs
+            .push(Expr::Param(Param::OpenedDate(opened_date.into())));
         self
     }
     pub fn closed_date(mut self, closed_date: impl Into<Date>) -> Self {
-        self.0.push(Param::ClosedDate(closed_date.into()));
+        self.exprs
+            .push(Expr::Param(Param::ClosedDate(closed_date.into())));
+        self
+    }
+    pub fn project(mut self, project: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::Project(project.as_ref().to_string())));
+        self
+    }
+    pub fn area(mut self, area: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::Area(area.as_ref().to_string())));
+        self
+    }
+    pub fn milestone(mut self, milestone: impl AsRef<str>) -> Self {
+        self.exprs.push(Expr::Param(Param::Milestone(
+            milestone.as_ref().to_string(),
+        )));
+        self
+    }
+    pub fn status(mut self, status: StatusFilter) -> Self {
+        self.exprs.push(Expr::Param(Param::Status(status)));
+        self
+    }
+    pub fn category(mut self, category: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::Category(category.as_ref().to_string())));
+        self
+    }
+    pub fn priority(mut self, priority: impl Into<Priority>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::Priority(priority.into())));
+        self
+    }
+    pub fn tag(mut self, tag: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::Tag(tag.as_ref().to_string())));
+        self
+    }
+    pub fn edited_date(mut self, edited_date: impl Into<Date>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::EditedDate(edited_date.into())));
+        self
+    }
+    pub fn resolved_date(mut self, resolved_date: impl Into<Date>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::ResolvedDate(resolved_date.into())));
+        self
+    }
+    pub fn due_date(mut self, due_date: impl Into<Date>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::DueDate(due_date.into())));
+        self
+    }
+    pub fn last_edited_date(mut self, last_edited_date: impl Into<Date>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::LastEditedDate(last_edited_date.into())));
+        self
+    }
+    pub fn opened_by(mut self, opened_by: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::OpenedBy(opened_by.as_ref().to_string())));
+        self
+    }
+    pub fn resolved_by(mut self, resolved_by: impl AsRef<str>) -> Self {
+        self.exprs.push(Expr::Param(Param::ResolvedBy(
+            resolved_by.as_ref().to_string(),
+        )));
+        self
+    }
+    pub fn correspondent(mut self, correspondent: impl AsRef<str>) -> Self {
+        self.exprs.push(Expr::Param(Param::Correspondent(
+            correspondent.as_ref().to_string(),
+        )));
+        self
+    }
+    pub fn parent(mut self, case_id: u64) -> Self {
+        self.exprs.push(Expr::Param(Param::Parent(case_id)));
+        self
+    }
+    pub fn children(mut self, case_id: u64) -> Self {
+        self.exprs.push(Expr::Param(Param::Children(case_id)));
+        self
+    }
+    pub fn starred_by(mut self, starred_by: impl AsRef<str>) -> Self {
+        self.exprs.push(Expr::Param(Param::StarredBy(
+            starred_by.as_ref().to_string(),
+        )));
+        self
+    }
+    pub fn viewed_by(mut self, viewed_by: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::ViewedBy(viewed_by.as_ref().to_string())));
+        self
+    }
+    pub fn subscribed_by(mut self, subscribed_by: impl AsRef<str>) -> Self {
+        self.exprs.push(Expr::Param(Param::SubscribedBy(
+            subscribed_by.as_ref().to_string(),
+        )));
+        self
+    }
+    /// Cases never viewed by the person. Cases changed since they were last
+    /// viewed are not matched, see [`Case::is_unread`](crate::search::Case::is_unread).
+    pub fn unread_by(mut self, person: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Not(Box::new(Expr::Param(Param::ViewedBy(
+                person.as_ref().to_string(),
+            )))));
+        self
+    }
+    /// Free-text search over titles and events.
+    pub fn text(mut self, text: impl AsRef<str>) -> Self {
+        self.exprs
+            .push(Expr::Param(Param::Text(text.as_ref().to_string())));
+        self
+    }
+    /// Matches cases where the custom field equals `value`.
+    pub fn custom_field(mut self, field: &CustomField, value: impl AsRef<str>) -> Self {
+        self.exprs.push(Expr::Param(field.matches(value)));
+        self
+    }
+    /// Matches cases that do not match `query`.
+    pub fn not(mut self, query: impl IntoQuery) -> Self {
+        self.exprs
+            .push(Expr::Not(Box::new(query.into_query().expr.simplified())));
+        self
+    }
+    /// Matches cases that match at least one of `queries`.
+    pub fn any_of<Q: IntoQuery>(mut self, queries: impl IntoIterator<Item = Q>) -> Self {
+        self.exprs.push(Expr::Or(
+            queries
+                .into_iter()
+                .map(|q| q.into_query().expr.simplified())
+                .collect(),
+        ));
+        self
+    }
+    /// Adds `query` wrapped in parentheses.
+    pub fn group(mut self, query: impl IntoQuery) -> Self {
+        self.exprs
+            .push(Expr::Group(Box::new(query.into_query().expr)));
+        self
+    }
+    /// Adds an ordering key, keys added first take precedence.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by.push(order_by);
+        self
+    }
+    /// Caps the number of returned cases.
+    pub fn max(mut self, max: u32) -> Self {
+        self.max = Some(max);
         self
     }
     pub fn build(self) -> Query {
-        let mut query = Query {
-            case_id: None,
-            assigned_to: None,
-            from_email: None,
-            opened_date: None,
-            closed_date: None,
-        };
-        for param in self.0 {
-            match param {
-                Param::CaseId(case_id) => query.case_id = Some(case_id),
-                Param::AssignedTo(assigned_to) => query.assigned_to = Some(assigned_to),
-                Param::FromEmail(from_email) => query.from_email = Some(from_email),
-                Param::OpenedDate(opened_date) => query.opened_date = Some(opened_date),
-                Param::ClosedDate(closed_date) => query.closed_date = Some(closed_date),
-            }
+        Query {
+            expr: Expr::And(self.exprs),
+            order_by: self.order_by,
+            max: self.max,
         }
-        query
     }
 }
 
@@ -109,6 +526,66 @@ impl Query {
     pub fn builder() -> QueryBuilder {
         QueryBuilder::new()
     }
+    /// Matches cases matching both queries. Ordering keys of `other` are
+    /// appended, its `max` is used only if this query has none.
+    pub fn and(self, other: impl IntoQuery) -> Query {
+        let other = other.into_query();
+        let expr = match self.expr {
+            Expr::And(mut exprs) => {
+                exprs.push(other.expr.simplified());
+                Expr::And(exprs)
+            }
+            expr => Expr::And(vec![expr, other.expr.simplified()]),
+        };
+        Query {
+            expr,
+            order_by: [self.order_by, other.order_by].concat(),
+            max: self.max.or(other.max),
+        }
+    }
+    /// Matches cases matching either query. Ordering keys of `other` are
+    /// appended, its `max` is used only if this query has none.
+    pub fn or(self, other: impl IntoQuery) -> Query {
+        let other = other.into_query();
+        let expr = match self.expr.simplified() {
+            Expr::Or(mut exprs) => {
+                exprs.push(other.expr.simplified());
+                Expr::Or(exprs)
+            }
+            expr => Expr::Or(vec![expr, other.expr.simplified()]),
+        };
+        Query {
+            expr,
+            order_by: [self.order_by, other.order_by].concat(),
+            max: self.max.or(other.max),
+        }
+    }
+    /// Wraps the query in parentheses.
+    pub fn group(self) -> Query {
+        Query {
+            expr: Expr::Group(Box::new(self.expr)),
+            ..self
+        }
+    }
+    pub fn order_by(mut self, order_by: OrderBy) -> Query {
+        self.order_by.push(order_by);
+        self
+    }
+    pub fn max(mut self, max: u32) -> Query {
+        self.max = Some(max);
+        self
+    }
+}
+
+impl Not for Query {
+    type Output = Query;
+
+    fn not(self) -> Query {
+        Query {
+            expr: Expr::Not(Box::new(self.expr)),
+            ..self
+        }
+    }
 }
 
 pub trait IntoQuery {
@@ -126,3 +603,522 @@ impl IntoQuery for QueryBuilder {
         self.build()
     }
 }
+
+impl IntoQuery for Param {
+    fn into_query(self) -> Query {
+        Query::from(Expr::Param(self))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseErrorKind {
+    #[error("unexpected end of query")]
+    UnexpectedEnd,
+    #[error("unexpected character `{0}`")]
+    UnexpectedChar(char),
+    #[error("unterminated quoted string")]
+    UnterminatedQuote,
+    #[error("missing value for `{0}`")]
+    MissingValue(String),
+    #[error("invalid value `{value}` for `{axis}`")]
+    InvalidValue { axis: String, value: String },
+}
+
+/// Error returned when parsing a [`Query`], `position` is the byte offset of
+/// the offending input.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{kind} at position {position}")]
+pub struct ParseError {
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    /// Parses FogBugz search syntax, e.g. `project:"Web" -status:closed`.
+    /// `orderBy:` terms are collected into [`Query::order_by`] wherever they
+    /// appear.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            position: 0,
+            order_by: Vec::new(),
+        };
+        let expr = parser.parse_or()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(Query {
+                expr,
+                order_by: parser.order_by,
+                max: None,
+            }),
+            Some(c) => Err(parser.error(ParseErrorKind::UnexpectedChar(c))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Query {
+    fn deserialize<D>(deserializer: D) -> Result<Query, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<Query>().map_err(serde::de::Error::custom)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    order_by: Vec<OrderBy>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            position: self.position,
+            kind,
+        }
+    }
+    /// Returns the bare word at the cursor without consuming it.
+    fn peek_word(&self) -> &str {
+        let rest = &self.input[self.position..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || "()\"".contains(c))
+            .unwrap_or(rest.len());
+        &rest[..end]
+    }
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut branches = vec![self.parse_and()?];
+        loop {
+            self.skip_whitespace();
+            if !self.peek_word().eq_ignore_ascii_case("or") {
+                break;
+            }
+            self.position += 2;
+            branches.push(self.parse_and()?);
+        }
+        if branches.len() == 1 {
+            return Ok(branches.remove(0));
+        }
+        let branches = branches
+            .into_iter()
+            .map(|branch| match branch {
+                Expr::And(mut exprs) if exprs.len() == 1 => exprs.remove(0),
+                branch => branch,
+            })
+            .collect();
+        Ok(Expr::Or(branches))
+    }
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut exprs = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None | Some(')') => break,
+                _ => {}
+            }
+            let word = self.peek_word();
+            if word.eq_ignore_ascii_case("or") {
+                break;
+            }
+            if word.eq_ignore_ascii_case("and") {
+                self.position += 3;
+                continue;
+            }
+            if let Some(expr) = self.parse_unary()? {
+                exprs.push(expr);
+            }
+        }
+        Ok(Expr::And(exprs))
+    }
+    /// Returns `None` for terms that are not part of the expression, i.e.
+    /// ordering keys.
+    fn parse_unary(&mut self) -> Result<Option<Expr>, ParseError> {
+        match self.peek() {
+            Some('-') => {
+                let minus = self.error(ParseErrorKind::UnexpectedChar('-'));
+                self.bump();
+                match self.parse_unary()? {
+                    Some(expr) => Ok(Some(Expr::Not(Box::new(expr)))),
+                    None => Err(minus),
+                }
+            }
+            Some('(') => {
+                self.bump();
+                let expr = self.parse_or()?;
+                self.skip_whitespace();
+                match self.bump() {
+                    Some(')') => Ok(Some(expr)),
+                    Some(c) => Err(self.error(ParseErrorKind::UnexpectedChar(c))),
+                    None => Err(self.error(ParseErrorKind::UnexpectedEnd)),
+                }
+            }
+            Some('"') => {
+                let text = self.parse_quoted()?;
+                if self.peek() == Some(':') {
+                    self.bump();
+                    return self.parse_value(text);
+                }
+                Ok(Some(Expr::Param(Param::Text(text))))
+            }
+            Some(c) if c.is_whitespace() || c == ')' => {
+                Err(self.error(ParseErrorKind::UnexpectedChar(c)))
+            }
+            Some(_) => self.parse_term(),
+            None => Err(self.error(ParseErrorKind::UnexpectedEnd)),
+        }
+    }
+    fn parse_quoted(&mut self) -> Result<String, ParseError> {
+        let start = self.position;
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                Some(c) => value.push(c),
+                None => break,
+            }
+        }
+        Err(ParseError {
+            position: start,
+            kind: ParseErrorKind::UnterminatedQuote,
+        })
+    }
+    fn parse_term(&mut self) -> Result<Option<Expr>, ParseError> {
+        let word = self.peek_word();
+        let Some(colon) = word.find(':') else {
+            let text = word.to_string();
+            self.position += text.len();
+            return Ok(Some(Expr::Param(Param::Text(text))));
+        };
+        let axis = word[..colon].to_string();
+        self.position += colon + 1;
+        self.parse_value(axis)
+    }
+    /// Value of an `axis:value` term, the position is right after the colon.
+    fn parse_value(&mut self, axis: String) -> Result<Option<Expr>, ParseError> {
+        let value_position = self.position;
+        let value = match self.peek() {
+            Some('"') => self.parse_quoted()?,
+            _ => {
+                let value = self.peek_word().to_string();
+                self.position += value.len();
+                value
+            }
+        };
+        if value.is_empty() && self.position == value_position {
+            return Err(ParseError {
+                position: value_position,
+                kind: ParseErrorKind::MissingValue(axis),
+            });
+        }
+        let invalid = |value: String| ParseError {
+            position: value_position,
+            kind: ParseErrorKind::InvalidValue {
+                axis: axis.clone(),
+                value,
+            },
+        };
+        if axis.eq_ignore_ascii_case("orderby") {
+            let order_by = value.parse::<OrderBy>().map_err(|_| invalid(value))?;
+            self.order_by.push(order_by);
+            return Ok(None);
+        }
+        Param::from_axis(&axis, value)
+            .map(|param| Some(Expr::Param(param)))
+            .map_err(|kind| ParseError {
+                position: value_position,
+                kind,
+            })
+    }
+}
+
+impl Param {
+    /// Builds the param for a FogBugz search axis, matched case-insensitively.
+    /// Axes without a typed param become [`Param::Other`].
+    fn from_axis(axis: &str, value: String) -> Result<Param, ParseErrorKind> {
+        let invalid = |value: &str| ParseErrorKind::InvalidValue {
+            axis: axis.to_string(),
+            value: value.to_string(),
+        };
+        let case_id = |value: &str| value.parse::<u64>().map_err(|_| invalid(value));
+        let date = |value: &str| value.parse::<Date>().map_err(|_| invalid(value));
+        let param = match axis.to_ascii_lowercase().as_str() {
+            "ixbug" => Param::CaseId(case_id(&value)?),
+            "assignedto" => Param::AssignedTo(match value.parse() {
+                Ok(id) => PersonRef::Id(id),
+                Err(_) => PersonRef::from(value),
+            }),
+            "from" => Param::FromEmail(value),
+            "opened" => Param::OpenedDate(date(&value)?),
+            "closed" => Param::ClosedDate(date(&value)?),
+            "project" => Param::Project(value),
+            "area" => Param::Area(value),
+            "milestone" => Param::Milestone(value),
+            "status" => Param::Status(match value.to_ascii_lowercase().as_str() {
+                "open" => StatusFilter::Open,
+                "closed" => StatusFilter::Closed,
+                "active" => StatusFilter::Active,
+                "resolved" => StatusFilter::Resolved,
+                _ => StatusFilter::Named(value),
+            }),
+            "category" => Param::Category(value),
+            "priority" => Param::Priority(Priority(value.parse().map_err(|_| invalid(&value))?)),
+            "tag" => Param::Tag(value),
+            "edited" => Param::EditedDate(date(&value)?),
+            "resolved" => Param::ResolvedDate(date(&value)?),
+            "due" => Param::DueDate(date(&value)?),
+            "lastedited" => Param::LastEditedDate(date(&value)?),
+            "openedby" => Param::OpenedBy(value),
+            "resolvedby" => Param::ResolvedBy(value),
+            "correspondent" => Param::Correspondent(value),
+            "parent" => Param::Parent(case_id(&value)?),
+            "children" => Param::Children(case_id(&value)?),
+            "starredby" => Param::StarredBy(value),
+            "viewedby" => Param::ViewedBy(value),
+            "subscribedby" => Param::SubscribedBy(value),
+            _ => Param::Other(axis.to_string(), value),
+        };
+        Ok(param)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::PointInTime;
+
+    #[test]
+    fn test_display_search_axes() {
+        let query = Query::builder()
+            .project("Web Site")
+            .status(StatusFilter::Active)
+            .category("Bug")
+            .priority(1)
+            .due_date(PointInTime::new(1, 2, 2024))
+            .parent(42)
+            .text("crash")
+            .build();
+        assert_eq!(
+            query.to_string(),
+            "project:\"Web Site\" status:active category:Bug priority:1 due:\"1-2-2024\" parent:42 crash"
+        );
+    }
+
+    #[test]
+    fn test_display_assigned_to() {
+        let query = Query::builder()
+            .assigned_to(7)
+            .assigned_to("Jane Doe")
+            .assigned_to("jane@example.com")
+            .build();
+        assert_eq!(
+            query.to_string(),
+            "assignedTo:7 assignedTo:\"Jane Doe\" assignedTo:jane@example.com"
+        );
+        assert_eq!(query.to_string().parse::<Query>().unwrap(), query);
+    }
+
+    #[test]
+    fn test_display_read_state_axes() {
+        let query = Query::builder()
+            .subscribed_by("me")
+            .unread_by("me")
+            .starred_by("Jane Doe")
+            .build();
+        assert_eq!(
+            query.to_string(),
+            "subscribedBy:me -viewedBy:me starredBy:\"Jane Doe\""
+        );
+        assert_eq!(query.to_string().parse::<Query>().unwrap(), query);
+    }
+
+    #[test]
+    fn test_display_boolean_composition() {
+        let query = Query::builder()
+            .project("Web")
+            .any_of([
+                Param::AssignedTo("Ann".into()),
+                Param::AssignedTo("Bob Smith".into()),
+            ])
+            .not(Query::builder().tag("wontfix").tag("spam"))
+            .build();
+        assert_eq!(
+            query.to_string(),
+            "project:Web (assignedTo:Ann OR assignedTo:\"Bob Smith\") -(tag:wontfix tag:spam)"
+        );
+
+        let query = Param::Project("Web".to_string())
+            .into_query()
+            .or(Param::Project("Api".to_string()))
+            .group()
+            .and(!Param::Status(StatusFilter::Closed).into_query());
+        assert_eq!(
+            query.to_string(),
+            "(project:Web OR project:Api) -status:closed"
+        );
+
+        let query = !Query::builder()
+            .any_of([Query::builder().tag("a").tag("b")])
+            .build();
+        assert_eq!(query.to_string(), "-(tag:a tag:b)");
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query: Query = r#"project:"Web" status:active orderby:priority"#.parse().unwrap();
+        assert_eq!(
+            query,
+            Query::builder()
+                .project("Web")
+                .status(StatusFilter::Active)
+                .order_by(OrderBy::asc(SortField::Priority))
+                .build()
+        );
+        assert_eq!(
+            query.to_string(),
+            "project:Web status:active orderBy:Priority"
+        );
+
+        let query: Query = "ixBug:1 OR -(tag:\"say \\\"hi\\\"\" AND priority:2) crash"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            query.expr,
+            Expr::Or(vec![
+                Expr::Param(Param::CaseId(1)),
+                Expr::And(vec![
+                    Expr::Not(Box::new(Expr::And(vec![
+                        Expr::Param(Param::Tag("say \"hi\"".to_string())),
+                        Expr::Param(Param::Priority(Priority(2))),
+                    ]))),
+                    Expr::Param(Param::Text("crash".to_string())),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for input in [
+            "",
+            "project:\"Web Site\" -status:closed",
+            "(assignedTo:Ann OR assignedTo:\"Bob Smith\") category:bug due:\"1-2-2024..3-2-2024\"",
+            "-(tag:a tag:b) OR \"free text\" OR parent:42",
+            "customer:\"ACME (EU)\" viewedBy:me",
+            "orderBy:\"-Due\" status:open OrderBy:case",
+        ] {
+            let query: Query = input.parse().unwrap();
+            let rendered = query.to_string();
+            let reparsed: Query = rendered.parse().unwrap();
+            assert_eq!(reparsed, query, "{}", input);
+            assert_eq!(reparsed.to_string(), rendered);
+        }
+    }
+
+    #[test]
+    fn test_builder_queries_round_trip() {
+        let web = Param::Project("Web".to_string());
+        let bug = Param::Category("bug".to_string());
+        for query in [
+            Query::builder()
+                .project("Web")
+                .any_of([
+                    Param::AssignedTo("Ann".into()),
+                    Param::AssignedTo("Bob Smith".into()),
+                ])
+                .not(Query::builder().tag("wontfix").tag("spam"))
+                .build(),
+            Query::builder()
+                .not(Query::builder().tag("wontfix"))
+                .any_of([
+                    Query::builder().tag("a"),
+                    Query::builder().tag("b").priority(1),
+                ])
+                .build(),
+            web.clone()
+                .into_query()
+                .or(Query::builder().tag("a").status(StatusFilter::Open))
+                .or(bug.clone()),
+            Query::builder()
+                .text("crash")
+                .build()
+                .and(web.into_query().or(bug))
+                .order_by(OrderBy::desc(SortField::Priority)),
+        ] {
+            let reparsed: Query = query.to_string().parse().unwrap();
+            assert_eq!(reparsed, query, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors_carry_positions() {
+        let err = "project:\"Web".parse::<Query>().unwrap_err();
+        assert_eq!(err.position, 8);
+        assert_eq!(err.kind, ParseErrorKind::UnterminatedQuote);
+
+        let err = "tag:a priority:high".parse::<Query>().unwrap_err();
+        assert_eq!(err.position, 15);
+        assert!(matches!(err.kind, ParseErrorKind::InvalidValue { .. }));
+
+        let err = "(tag:a".parse::<Query>().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEnd);
+
+        let err = "tag:a)".parse::<Query>().unwrap_err();
+        assert_eq!(err.position, 5);
+
+        let err = "project: x".parse::<Query>().unwrap_err();
+        assert_eq!(
+            err.kind,
+            ParseErrorKind::MissingValue("project".to_string())
+        );
+    }
+
+    #[test]
+    fn test_order_by_and_max() {
+        let query = Query::builder()
+            .status(StatusFilter::Open)
+            .order_by(OrderBy::desc(SortField::Priority))
+            .order_by(OrderBy::asc(SortField::DueDate))
+            .max(10)
+            .build();
+        assert_eq!(
+            query.to_string(),
+            "status:open orderBy:\"-Priority\" orderBy:Due"
+        );
+        assert_eq!(query.max, Some(10));
+
+        let err = "orderBy:-nonsense".parse::<Query>().unwrap_err();
+        assert_eq!(err.position, 8);
+        let err = "-orderBy:Title".parse::<Query>().unwrap_err();
+        assert_eq!(err.position, 0);
+    }
+
+    #[test]
+    fn test_quote_escapes_values() {
+        assert_eq!(quote("plain"), "plain");
+        assert_eq!(quote("OR"), "\"OR\"");
+        assert_eq!(quote("-x"), "\"-x\"");
+        assert_eq!(quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote("a\\b"), "\"a\\\\b\"");
+    }
+}
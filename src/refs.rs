@@ -0,0 +1,227 @@
+use thiserror::Error;
+
+use crate::{
+    cache::MetadataRecord,
+    metadata::{Person, Project},
+    FogbugzApi, ResponseError,
+};
+
+/// Person given by id, full name or email address. Names are resolved
+/// through `listPeople` before a request is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PersonRef {
+    Id(u64),
+    FullName(String),
+    Email(String),
+}
+
+/// Project given by id or name. Names are resolved through `listProjects`
+/// before a request is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectRef {
+    Id(u64),
+    Name(String),
+}
+
+#[derive(Debug, Error)]
+pub enum ResolveError {
+    #[error("No {kind} matches {name:?}")]
+    NotFound { kind: &'static str, name: String },
+    #[error("{name:?} is ambiguous, it matches the {kind} ids {ids:?}")]
+    Ambiguous {
+        kind: &'static str,
+        name: String,
+        ids: Vec<u64>,
+    },
+    /// Several filters share the name; filter ids are strings, built-in
+    /// filters have ids like `ez`.
+    #[error("{name:?} is ambiguous, it matches the filter ids {ids:?}")]
+    AmbiguousFilter { name: String, ids: Vec<String> },
+}
+
+impl PersonRef {
+    /// Person id, looked up on the server unless given directly.
+    pub async fn resolve(&self, api: &FogbugzApi) -> Result<u64, ResponseError> {
+        match self {
+            PersonRef::Id(id) => Ok(*id),
+            PersonRef::FullName(name) => {
+                let people = api.metadata::<Person>().await?;
+                let matches = people
+                    .iter()
+                    .filter(|person| person.full_name.eq_ignore_ascii_case(name));
+                unique("person", name, matches)
+            }
+            PersonRef::Email(email) => {
+                let people = api.metadata::<Person>().await?;
+                let matches = people.iter().filter(|person| {
+                    person
+                        .email
+                        .as_deref()
+                        .is_some_and(|e| e.eq_ignore_ascii_case(email))
+                });
+                unique("person", email, matches)
+            }
+        }
+    }
+}
+
+impl ProjectRef {
+    /// Project id, looked up on the server unless given directly.
+    pub async fn resolve(&self, api: &FogbugzApi) -> Result<u64, ResponseError> {
+        match self {
+            ProjectRef::Id(id) => Ok(*id),
+            ProjectRef::Name(name) => {
+                let projects = api.metadata::<Project>().await?;
+                unique(
+                    "project",
+                    name,
+                    projects.iter().filter(|p| p.matches_name(name)),
+                )
+            }
+        }
+    }
+}
+
+pub(crate) fn unique<'a, T: MetadataRecord>(
+    kind: &'static str,
+    name: &str,
+    matches: impl Iterator<Item = &'a T>,
+) -> Result<u64, ResponseError> {
+    let ids: Vec<u64> = matches.map(MetadataRecord::id).collect();
+    match ids.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(ResolveError::NotFound {
+            kind,
+            name: name.to_string(),
+        }
+        .into()),
+        _ => Err(ResolveError::Ambiguous {
+            kind,
+            name: name.to_string(),
+            ids,
+        }
+        .into()),
+    }
+}
+
+impl From<u64> for PersonRef {
+    fn from(id: u64) -> Self {
+        PersonRef::Id(id)
+    }
+}
+
+/// Strings containing `@` are taken as email addresses.
+impl From<&str> for PersonRef {
+    fn from(name: &str) -> Self {
+        if name.contains('@') {
+            PersonRef::Email(name.to_string())
+        } else {
+            PersonRef::FullName(name.to_string())
+        }
+    }
+}
+
+impl From<String> for PersonRef {
+    fn from(name: String) -> Self {
+        PersonRef::from(name.as_str())
+    }
+}
+
+impl From<&String> for PersonRef {
+    fn from(name: &String) -> Self {
+        PersonRef::from(name.as_str())
+    }
+}
+
+impl From<&Person> for PersonRef {
+    fn from(person: &Person) -> Self {
+        PersonRef::Id(person.id)
+    }
+}
+
+impl From<u64> for ProjectRef {
+    fn from(id: u64) -> Self {
+        ProjectRef::Id(id)
+    }
+}
+
+impl From<&str> for ProjectRef {
+    fn from(name: &str) -> Self {
+        ProjectRef::Name(name.to_string())
+    }
+}
+
+impl From<String> for ProjectRef {
+    fn from(name: String) -> Self {
+        ProjectRef::Name(name)
+    }
+}
+
+impl From<&String> for ProjectRef {
+    fn from(name: &String) -> Self {
+        ProjectRef::Name(name.clone())
+    }
+}
+
+impl From<&Project> for ProjectRef {
+    fn from(project: &Project) -> Self {
+        ProjectRef::Id(project.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cache::{MetadataCache, DEFAULT_TTL},
+        test_api_builder,
+    };
+
+    const SNAPSHOT: &str = r#"{
+        "projects": [
+            {"ixProject": 1, "sProject": "Inbox"},
+            {"ixProject": 2, "sProject": "Website"}
+        ],
+        "people": [
+            {"ixPerson": 1, "sFullName": "Administrator", "sEmail": "admin@example.com"},
+            {"ixPerson": 7, "sFullName": "Jane Doe", "sEmail": "jane@example.com"},
+            {"ixPerson": 8, "sFullName": "Jane Doe", "sEmail": "jane.doe@example.com"}
+        ]
+    }"#;
+
+    fn api() -> FogbugzApi {
+        test_api_builder()
+            .cache(MetadataCache::from_json(DEFAULT_TTL, SNAPSHOT).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_resolve_refs() {
+        let api = api();
+        assert_eq!(PersonRef::from(3).resolve(&api).await.unwrap(), 3);
+        let person = PersonRef::from("JANE@example.com");
+        assert_eq!(person, PersonRef::Email("JANE@example.com".to_string()));
+        assert_eq!(person.resolve(&api).await.unwrap(), 7);
+        assert_eq!(
+            PersonRef::from("administrator")
+                .resolve(&api)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(ProjectRef::from("Website").resolve(&api).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_errors() {
+        let api = api();
+        let err = PersonRef::from("Jane Doe").resolve(&api).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ResponseError::ResolveError(ResolveError::Ambiguous { ref ids, .. }) if ids == &[7, 8]
+        ));
+        let err = ProjectRef::from("Nope").resolve(&api).await.unwrap_err();
+        assert_eq!(err.to_string(), "No project matches \"Nope\"");
+    }
+}
@@ -1,19 +1,33 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
 use reqwest::Url;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
 
 use crate::{
-    enums::Column,
-    query::{IntoQuery, Query},
+    case_details::Event,
+    custom_fields::{self, CustomFieldValues},
+    enums::{Category, Column, Priority, ResolveStatus, Status, StatusRegistry},
+    query::{IntoQuery, OrderBy, Query},
     FogbugzApi, ResponseError,
 };
 
-#[derive(Debug, Serialize)]
+/// Number of cases fetched per request by [`SearchRequest::stream`].
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct SearchRequest {
     #[serde(rename = "q")]
     query: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     cols: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<u32>,
+    #[serde(skip)]
+    page_size: usize,
     token: String,
     #[serde(skip)]
     api: FogbugzApi,
@@ -23,6 +37,9 @@ pub struct SearchRequest {
 pub struct SearchRequestBuilder {
     query: Option<Query>,
     cols: Option<Vec<String>>,
+    order_by: Vec<OrderBy>,
+    max: Option<u32>,
+    page_size: usize,
     api: Option<FogbugzApi>,
 }
 
@@ -31,6 +48,9 @@ impl Default for SearchRequestBuilder {
         Self {
             query: None,
             cols: Some(vec![Column::CaseId.to_string(), Column::Title.to_string()]),
+            order_by: Vec::new(),
+            max: None,
+            page_size: DEFAULT_PAGE_SIZE,
             api: None,
         }
     }
@@ -42,27 +62,172 @@ pub enum SearchRequestBuilderError {
     QueryNotSpecified,
     #[error("Api is not specified")]
     ApiNotSpecified,
+    #[error("Page size must be greater than zero")]
+    InvalidPageSize,
 }
 
+/// Case returned by [`SearchRequest::send`] and [`SearchRequest::stream`].
+///
+/// Every field except the id maps to a [`Column`] and is `None` unless that
+/// column was requested.
 #[derive(Debug, Deserialize)]
-pub struct Event {
-    #[serde(rename = "evtDescription")]
-    pub description: String,
-    #[serde(rename = "ixPerson")]
-    pub person_id: u64,
-    #[serde(rename = "sPerson")]
-    pub person: String,
-    #[serde(rename = "s")]
-    pub content: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CaseDetails {
+pub struct Case {
     #[serde(rename = "ixBug")]
-    pub ticket_number: u64,
+    pub case_id: u64,
+    /// `None` for cases without a parent.
+    #[serde(rename = "ixBugParent", default, deserialize_with = "parent_id")]
+    pub parent_id: Option<u64>,
+    #[serde(rename = "ixBugChildren", default, deserialize_with = "id_list")]
+    pub child_ids: Option<Vec<u64>>,
+    #[serde(rename = "ixBugDuplicates", default, deserialize_with = "id_list")]
+    pub duplicate_ids: Option<Vec<u64>>,
+    #[serde(rename = "ixBugOriginal")]
+    pub original_id: Option<u64>,
+    #[serde(rename = "ixRelatedBugs", default, deserialize_with = "id_list")]
+    pub related_ids: Option<Vec<u64>>,
+    #[serde(rename = "tags")]
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "fOpen")]
+    pub is_open: Option<bool>,
     #[serde(rename = "sTitle")]
-    pub title: String,
-    pub events: Vec<Event>,
+    pub title: Option<String>,
+    #[serde(rename = "sOriginalTitle")]
+    pub original_title: Option<String>,
+    #[serde(rename = "sLatestTextSummary")]
+    pub latest_text_summary: Option<String>,
+    #[serde(rename = "ixBugEventLatestText")]
+    pub latest_text_event_id: Option<u64>,
+    #[serde(rename = "sHtmlBody")]
+    pub body: Option<String>,
+    #[serde(rename = "ixProject")]
+    pub project_id: Option<u64>,
+    #[serde(rename = "sProject")]
+    pub project: Option<String>,
+    #[serde(rename = "ixArea")]
+    pub area_id: Option<u64>,
+    #[serde(rename = "sArea")]
+    pub area: Option<String>,
+    #[serde(rename = "ixGroup")]
+    pub group_id: Option<u64>,
+    #[serde(rename = "ixPersonAssignedTo")]
+    pub assigned_to_id: Option<u64>,
+    #[serde(rename = "sPersonAssignedTo")]
+    pub assigned_to: Option<String>,
+    #[serde(rename = "sEmailAssignedTo")]
+    pub assigned_to_email: Option<String>,
+    #[serde(rename = "ixPersonOpenedBy")]
+    pub opened_by_id: Option<u64>,
+    #[serde(rename = "ixPersonResolvedBy")]
+    pub resolved_by_id: Option<u64>,
+    #[serde(rename = "ixPersonClosedBy")]
+    pub closed_by_id: Option<u64>,
+    #[serde(rename = "ixPersonLastEditedBy")]
+    pub last_edited_by_id: Option<u64>,
+    #[serde(rename = "ixStatus")]
+    pub status: Option<Status>,
+    #[serde(rename = "sStatus")]
+    pub status_name: Option<String>,
+    #[serde(rename = "ixPriority")]
+    pub priority: Option<Priority>,
+    #[serde(rename = "sPriority")]
+    pub priority_name: Option<String>,
+    #[serde(rename = "ixCategory")]
+    pub category: Option<Category>,
+    #[serde(rename = "sCategory")]
+    pub category_name: Option<String>,
+    #[serde(rename = "ixFixFor")]
+    pub milestone_id: Option<u64>,
+    #[serde(rename = "sFixFor")]
+    pub milestone: Option<String>,
+    #[serde(rename = "dtFixFor")]
+    pub milestone_date: Option<DateTime<Utc>>,
+    #[serde(rename = "nFixForOrder")]
+    pub milestone_order: Option<u64>,
+    #[serde(rename = "sVersion")]
+    pub version: Option<String>,
+    #[serde(rename = "sComputer")]
+    pub computer: Option<String>,
+    #[serde(rename = "hrsOrigEst")]
+    pub original_estimate: Option<f64>,
+    #[serde(rename = "hrsCurrEst")]
+    pub current_estimate: Option<f64>,
+    #[serde(rename = "hrsElapsed")]
+    pub elapsed: Option<f64>,
+    #[serde(rename = "dblStoryPts")]
+    pub story_points: Option<f64>,
+    #[serde(rename = "c")]
+    pub occurrences: Option<u64>,
+    #[serde(rename = "sCustomerEmail")]
+    pub customer_email: Option<String>,
+    #[serde(rename = "ixMailbox")]
+    pub mailbox_id: Option<u64>,
+    #[serde(rename = "dtOpened")]
+    pub opened: Option<DateTime<Utc>>,
+    #[serde(rename = "dtResolved")]
+    pub resolved: Option<DateTime<Utc>>,
+    #[serde(rename = "dtClosed")]
+    pub closed: Option<DateTime<Utc>>,
+    #[serde(rename = "dtDue")]
+    pub due: Option<DateTime<Utc>>,
+    #[serde(rename = "dtLastUpdated")]
+    pub last_updated: Option<DateTime<Utc>>,
+    #[serde(rename = "ixBugEventLatest")]
+    pub latest_event_id: Option<u64>,
+    #[serde(rename = "ixBugEventLastView")]
+    pub last_viewed_event_id: Option<u64>,
+    #[serde(rename = "dtLastView")]
+    pub last_viewed: Option<DateTime<Utc>>,
+    #[serde(rename = "fReplied")]
+    pub is_replied: Option<bool>,
+    #[serde(rename = "fForwarded")]
+    pub is_forwarded: Option<bool>,
+    #[serde(rename = "fSubscribed")]
+    pub is_subscribed: Option<bool>,
+    #[serde(rename = "fStarred")]
+    pub is_starred: Option<bool>,
+    #[serde(rename = "sTicket")]
+    pub ticket: Option<String>,
+    #[serde(rename = "ixDiscussTopic")]
+    pub discuss_topic_id: Option<u64>,
+    #[serde(rename = "sReleaseNotes")]
+    pub release_notes: Option<String>,
+    #[serde(rename = "sScoutDescription")]
+    pub scout_description: Option<String>,
+    #[serde(rename = "sScoutMessage")]
+    pub scout_message: Option<String>,
+    #[serde(rename = "fScoutStopReporting")]
+    pub scout_stop_reporting: Option<bool>,
+    #[serde(rename = "dtLastOccurrence")]
+    pub last_occurrence: Option<DateTime<Utc>>,
+    #[serde(rename = "ixKanbanColumn")]
+    pub kanban_column_id: Option<u64>,
+    #[serde(rename = "sKanbanColumn")]
+    pub kanban_column: Option<String>,
+    #[serde(rename = "events")]
+    pub events: Option<Vec<Event>>,
+    #[serde(rename = "minievents")]
+    pub mini_events: Option<Vec<serde_json::Value>>,
+    /// Values of the requested [`Column::Custom`] columns.
+    #[serde(flatten, deserialize_with = "custom_fields::custom_field_values")]
+    pub custom_fields: CustomFieldValues,
+}
+
+impl ResolveStatus for Case {
+    fn resolve_status(&mut self, registry: &StatusRegistry) {
+        self.status.resolve_status(registry);
+    }
+}
+
+impl Case {
+    /// Whether the case changed since the current user last viewed it, needs
+    /// the `ixBugEventLatest` and `ixBugEventLastView` columns.
+    pub fn is_unread(&self) -> bool {
+        match (self.latest_event_id, self.last_viewed_event_id) {
+            (Some(latest), Some(viewed)) => latest > viewed,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
 }
 
 impl SearchRequestBuilder {
@@ -85,18 +250,39 @@ impl SearchRequestBuilder {
         }
         self
     }
+    /// Adds an ordering key after the ones already in the query.
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.order_by.push(order_by);
+        self
+    }
+    /// Caps the number of returned cases, overriding the query's `max`.
+    pub fn max(mut self, max: u32) -> Self {
+        self.max = Some(max);
+        self
+    }
+    /// Number of cases fetched per request when streaming.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size;
+        self
+    }
     pub fn api(mut self, api: FogbugzApi) -> Self {
         self.api = Some(api);
         self
     }
     pub fn build(self) -> Result<SearchRequest, SearchRequestBuilderError> {
-        let query = self
+        let mut query = self
             .query
             .ok_or(SearchRequestBuilderError::QueryNotSpecified)?;
+        query.order_by.extend(self.order_by);
+        if self.page_size == 0 {
+            return Err(SearchRequestBuilderError::InvalidPageSize);
+        }
         let api = self.api.ok_or(SearchRequestBuilderError::ApiNotSpecified)?;
         Ok(SearchRequest {
             query: query.to_string(),
             cols: self.cols,
+            max: self.max.or(query.max),
+            page_size: self.page_size,
             token: api.api_key.clone(),
             api,
         })
@@ -107,7 +293,119 @@ impl SearchRequest {
     pub fn builder() -> SearchRequestBuilder {
         SearchRequestBuilder::new()
     }
-    pub async fn send(&self) -> Result<serde_json::Value, ResponseError> {
+    /// Fetches the matching cases in a single request, deserialized as `T`.
+    /// Use [`SearchRequest::stream`] for large result sets and
+    /// [`SearchRequest::send_cases`] for [`Case`]s with resolved statuses.
+    pub async fn send<T: DeserializeOwned>(&self) -> Result<Vec<T>, ResponseError> {
+        let mut json = self.post().await?;
+        let cases = match json["data"]["cases"].take() {
+            serde_json::Value::Array(cases) => cases,
+            _ => Vec::new(),
+        };
+        cases
+            .into_iter()
+            .map(|mut case| {
+                retain_events(&mut case);
+                Ok(serde_json::from_value(case)?)
+            })
+            .collect()
+    }
+    /// Like [`SearchRequest::send`], with the statuses of the cases resolved.
+    pub async fn send_cases(&self) -> Result<Vec<Case>, ResponseError> {
+        let (statuses, mut cases) = tokio::try_join!(self.statuses(), self.send::<Case>())?;
+        cases
+            .iter_mut()
+            .for_each(|case| case.resolve_status(&statuses));
+        Ok(cases)
+    }
+    /// Streams every matching case, deserialized as `T`.
+    ///
+    /// The ids of all matching cases are fetched first, then the requested
+    /// columns are fetched in batches of `page_size` cases, one request per
+    /// batch. The stream ends after the first error.
+    pub fn stream<T>(&self) -> impl Stream<Item = Result<T, ResponseError>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let request = self.clone();
+        let (tx, rx) = mpsc::channel(self.page_size);
+        tokio::spawn(async move {
+            let case_ids = match request.case_ids().await {
+                Ok(case_ids) => case_ids,
+                Err(err) => {
+                    let _ = tx.send(Err(err)).await;
+                    return;
+                }
+            };
+            let fetch = |batch: Vec<u64>| {
+                let request = request.clone();
+                async move { request.page(&batch).await }
+            };
+            stream_pages(case_ids, request.max, request.page_size, fetch, tx).await;
+        });
+        ReceiverStream::new(rx)
+    }
+    /// Like [`SearchRequest::stream`], with the statuses of the cases
+    /// resolved. The registry is fetched before the stream is returned.
+    pub async fn stream_cases(
+        &self,
+    ) -> Result<impl Stream<Item = Result<Case, ResponseError>>, ResponseError> {
+        let statuses = self.statuses().await?;
+        Ok(self.stream::<Case>().map(move |case| {
+            case.map(|mut case| {
+                case.resolve_status(&statuses);
+                case
+            })
+        }))
+    }
+    /// Registry used to resolve statuses, only fetched when the
+    /// `ixStatus` column is requested.
+    async fn statuses(&self) -> Result<StatusRegistry, ResponseError> {
+        let status = Column::Status.to_string();
+        if self.cols.iter().flatten().any(|col| *col == status) {
+            self.api.statuses().await
+        } else {
+            Ok(StatusRegistry::default())
+        }
+    }
+    async fn case_ids(&self) -> Result<Vec<u64>, ResponseError> {
+        #[derive(Deserialize)]
+        struct CaseId {
+            #[serde(rename = "ixBug")]
+            case_id: u64,
+        }
+
+        let request = SearchRequest {
+            cols: Some(vec![Column::CaseId.to_string()]),
+            ..self.clone()
+        };
+        let mut json = request.post().await?;
+        let case_ids: Vec<CaseId> = serde_json::from_value(json["data"]["cases"].take())?;
+        Ok(case_ids.into_iter().map(|c| c.case_id).collect())
+    }
+    async fn page(&self, case_ids: &[u64]) -> Result<Vec<serde_json::Value>, ResponseError> {
+        let mut cols = self.cols.clone().unwrap_or_default();
+        if !cols.iter().any(|col| *col == Column::CaseId.to_string()) {
+            cols.push(Column::CaseId.to_string());
+        }
+        let request = SearchRequest {
+            query: case_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            cols: Some(cols),
+            max: None,
+            ..self.clone()
+        };
+        let mut json = request.post().await?;
+        let cases = match json["data"]["cases"].take() {
+            serde_json::Value::Array(cases) => cases,
+            _ => Vec::new(),
+        };
+        Ok(order_page(case_ids, cases))
+    }
+    async fn post(&self) -> Result<serde_json::Value, ResponseError> {
         let url = Url::parse(&self.api.url)?.join("api/search")?;
         #[cfg(feature = "leaky-bucket")]
         self.api.limiter.acquire_one().await;
@@ -122,9 +420,7 @@ impl SearchRequest {
             .await?;
 
         if response.status().is_success() {
-            let mut json: serde_json::Value = response.json().await?;
-            // let case = json["data"]["cases"][0].take();
-            // let case_details: CaseDetails = serde_json::from_value(case)?;
+            let json: serde_json::Value = response.json().await?;
             Ok(json)
         } else {
             let json: serde_json::Value = response.json().await?;
@@ -133,12 +429,119 @@ impl SearchRequest {
     }
 }
 
+/// Sends the first `max` of `case_ids` to `tx` as `T`, fetching them with
+/// `fetch` in batches of `page_size`. Stops after the first error or once the
+/// receiver is dropped.
+async fn stream_pages<T, F, Fut>(
+    mut case_ids: Vec<u64>,
+    max: Option<u32>,
+    page_size: usize,
+    mut fetch: F,
+    tx: mpsc::Sender<Result<T, ResponseError>>,
+) where
+    T: DeserializeOwned,
+    F: FnMut(Vec<u64>) -> Fut,
+    Fut: Future<Output = Result<Vec<serde_json::Value>, ResponseError>>,
+{
+    if let Some(max) = max {
+        case_ids.truncate(max as usize);
+    }
+    for batch in case_ids.chunks(page_size) {
+        let cases = match fetch(batch.to_vec()).await {
+            Ok(cases) => cases,
+            Err(err) => {
+                let _ = tx.send(Err(err)).await;
+                return;
+            }
+        };
+        for case in cases {
+            let case = serde_json::from_value::<T>(case).map_err(ResponseError::from);
+            let failed = case.is_err();
+            if tx.send(case).await.is_err() || failed {
+                return;
+            }
+        }
+    }
+}
+
+/// Fetches `cols` of the given cases with a single id query, in the order of
+/// `case_ids`.
+pub(crate) async fn fetch_cases(
+    api: &FogbugzApi,
+    case_ids: &[u64],
+    cols: &[Column],
+) -> Result<Vec<serde_json::Value>, ResponseError> {
+    let request = SearchRequest {
+        query: String::new(),
+        cols: Some(cols.iter().map(ToString::to_string).collect()),
+        max: None,
+        page_size: DEFAULT_PAGE_SIZE,
+        token: api.api_key.clone(),
+        api: api.clone(),
+    };
+    request.page(case_ids).await
+}
+
+/// Drops non-object entries from `events` before deserializing.
+fn retain_events(case: &mut serde_json::Value) {
+    if let serde_json::Value::Array(events) = &mut case["events"] {
+        events.retain(|event| matches!(event, serde_json::Value::Object(_)));
+    }
+}
+
+/// Puts the cases of a page back into the order of the id search, which the
+/// batched id query does not preserve. Also drops non-object `events` entries.
+fn order_page(case_ids: &[u64], mut cases: Vec<serde_json::Value>) -> Vec<serde_json::Value> {
+    cases.iter_mut().for_each(retain_events);
+    cases.sort_by_key(|case| {
+        case["ixBug"]
+            .as_u64()
+            .and_then(|id| case_ids.iter().position(|&case_id| case_id == id))
+            .unwrap_or(usize::MAX)
+    });
+    cases
+}
+
+/// FogBugz sends 0 as the parent of root cases.
+pub(crate) fn parent_id<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?.filter(|id| *id != 0))
+}
+
+/// Case id lists come as a comma separated string for some columns and as an
+/// array for others.
+pub(crate) fn id_list<'de, D>(deserializer: D) -> Result<Option<Vec<u64>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum IdList {
+        Ids(Vec<u64>),
+        Text(String),
+    }
+    match Option::<IdList>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(IdList::Ids(ids)) => Ok(Some(ids)),
+        Some(IdList::Text(text)) => text
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| id.parse().map_err(serde::de::Error::custom))
+            .collect::<Result<_, _>>()
+            .map(Some),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{date::PointInTime, FogbugzApiBuilder};
 
     #[tokio::test]
+    #[ignore = "requires FOGBUGZ_API_KEY and network access"]
     async fn test_search_request() {
         let api_key = std::env::var("FOGBUGZ_API_KEY").unwrap();
         #[cfg(feature = "leaky-bucket")]
@@ -147,18 +550,149 @@ mod tests {
             .interval(std::time::Duration::from_secs(1))
             .build();
 
-        let api = FogbugzApiBuilder::new()
+        let builder = FogbugzApiBuilder::new()
             .url("https://retailic.fogbugz.com")
-            .api_key(api_key)
-            .limiter(limiter)
-            .build()
-            .unwrap();
+            .api_key(api_key);
+        #[cfg(feature = "leaky-bucket")]
+        let builder = builder.limiter(limiter);
+        let api = builder.build().unwrap();
 
         let query = Query::builder()
             .closed_date((PointInTime::new(1, 1, 2024), PointInTime::new(31, 12, 2024)))
             .build();
         let request = api.search().query(query).build().unwrap();
-        let res = request.send().await.unwrap();
+        let res: Vec<Case> = request.send().await.unwrap();
         dbg!(res);
     }
+
+    #[test]
+    fn test_deserialize_case() {
+        let case: Case = serde_json::from_value(serde_json::json!({
+            "ixBug": 42,
+            "sTitle": "Printer on fire",
+            "ixPriority": 1,
+            "fOpen": true,
+            "operations": ["edit"],
+        }))
+        .unwrap();
+        assert_eq!(case.case_id, 42);
+        assert_eq!(case.title.as_deref(), Some("Printer on fire"));
+        assert_eq!(case.priority, Some(Priority(1)));
+        assert_eq!(case.is_open, Some(true));
+        assert!(case.project.is_none());
+        assert!(case.events.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_case_extended_columns() {
+        let case: Case = serde_json::from_value(serde_json::json!({
+            "ixBug": 42,
+            "ixBugParent": 0,
+            "ixBugChildren": "43, 44",
+            "ixRelatedBugs": "",
+            "ixBugDuplicates": [45],
+            "tags": ["hardware"],
+            "hrsCurrEst": 1.5,
+            "dtOpened": "2023-08-01T10:00:00Z",
+            "dtClosed": null,
+            "fSubscribed": false,
+        }))
+        .unwrap();
+        assert_eq!(case.parent_id, None);
+        assert_eq!(case.child_ids, Some(vec![43, 44]));
+        assert_eq!(case.related_ids, Some(vec![]));
+        assert_eq!(case.duplicate_ids, Some(vec![45]));
+        assert_eq!(case.tags, Some(vec!["hardware".to_string()]));
+        assert_eq!(case.current_estimate, Some(1.5));
+        assert!(case.opened.is_some());
+        assert!(case.closed.is_none());
+        assert_eq!(case.is_subscribed, Some(false));
+        assert!(case.original_id.is_none());
+        assert!(!case.is_unread());
+
+        let case: Case = serde_json::from_value(serde_json::json!({
+            "ixBug": 42,
+            "ixBugEventLatest": 310,
+            "ixBugEventLastView": 305,
+            "fStarred": true,
+        }))
+        .unwrap();
+        assert!(case.is_unread());
+        assert_eq!(case.is_starred, Some(true));
+    }
+
+    #[test]
+    fn test_order_page() {
+        let cases = vec![
+            serde_json::json!({"ixBug": 3}),
+            serde_json::json!({"ixBug": 1, "events": [{"evt": 1}, null]}),
+            serde_json::json!({"ixBug": 2}),
+        ];
+        let cases = order_page(&[2, 1, 3], cases);
+        let ids: Vec<_> = cases.iter().map(|c| c["ixBug"].as_u64().unwrap()).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert_eq!(cases[1]["events"], serde_json::json!([{"evt": 1}]));
+    }
+
+    /// Fetch answering each batch with `{"ixBug": id}` cases, failing the
+    /// batch starting with `fail_at`.
+    fn fetcher(
+        calls: &mut Vec<Vec<u64>>,
+        fail_at: Option<u64>,
+    ) -> impl FnMut(Vec<u64>) -> std::future::Ready<Result<Vec<serde_json::Value>, ResponseError>> + '_
+    {
+        move |case_ids| {
+            calls.push(case_ids.clone());
+            if case_ids.first() == fail_at.as_ref() {
+                let err = ResponseError::FogbugzError(serde_json::json!({"errors": []}));
+                return std::future::ready(Err(err));
+            }
+            let cases = case_ids
+                .iter()
+                .map(|id| serde_json::json!({ "ixBug": id }))
+                .collect();
+            std::future::ready(Ok(cases))
+        }
+    }
+
+    async fn collect(
+        case_ids: Vec<u64>,
+        max: Option<u32>,
+        fetch: impl FnMut(Vec<u64>) -> std::future::Ready<Result<Vec<serde_json::Value>, ResponseError>>,
+    ) -> Vec<Result<u64, ResponseError>> {
+        let (tx, mut rx) = mpsc::channel(16);
+        stream_pages::<serde_json::Value, _, _>(case_ids, max, 2, fetch, tx).await;
+        let mut cases = Vec::new();
+        while let Some(case) = rx.recv().await {
+            cases.push(case.map(|case| case["ixBug"].as_u64().unwrap()));
+        }
+        cases
+    }
+
+    #[tokio::test]
+    async fn test_stream_pages_batches_and_ends_on_short_page() {
+        let mut calls = Vec::new();
+        let cases = collect(vec![1, 2, 3, 4, 5], None, fetcher(&mut calls, None)).await;
+        let ids: Vec<u64> = cases.into_iter().map(Result::unwrap).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        assert_eq!(calls, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_pages_stops_at_max() {
+        let mut calls = Vec::new();
+        let cases = collect(vec![1, 2, 3, 4, 5], Some(3), fetcher(&mut calls, None)).await;
+        assert_eq!(cases.len(), 3);
+        assert_eq!(calls, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_pages_ends_after_failed_page() {
+        let mut calls = Vec::new();
+        let cases = collect(vec![1, 2, 3, 4, 5], None, fetcher(&mut calls, Some(3))).await;
+        assert_eq!(cases.len(), 3);
+        assert!(cases[..2].iter().all(Result::is_ok));
+        assert!(matches!(cases[2], Err(ResponseError::FogbugzError(_))));
+        assert_eq!(calls, vec![vec![1, 2], vec![3, 4]]);
+    }
 }
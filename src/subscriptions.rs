@@ -0,0 +1,185 @@
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{refs::PersonRef, send_command, FogbugzApi, ResponseError};
+
+/// Request shared by the commands that change how a user follows a case:
+/// `subscribe`, `unsubscribe`, `view`, `star` and `unstar`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionRequest {
+    #[serde(skip)]
+    command: SubscriptionCommand,
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    #[serde(rename = "ixPerson", skip_serializing_if = "Option::is_none")]
+    person_id: Option<u64>,
+    #[serde(skip)]
+    person: Option<PersonRef>,
+    #[serde(rename = "ixBugEvent", skip_serializing_if = "Option::is_none")]
+    event_id: Option<u64>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum SubscriptionCommand {
+    /// Sends notifications about changes of the case.
+    #[strum(serialize = "subscribe")]
+    Subscribe,
+    #[strum(serialize = "unsubscribe")]
+    Unsubscribe,
+    /// Marks the case as read, up to the given event or its latest one.
+    #[strum(serialize = "view")]
+    View,
+    #[strum(serialize = "star")]
+    Star,
+    #[strum(serialize = "unstar")]
+    Unstar,
+}
+
+#[derive(Debug)]
+pub struct SubscriptionRequestBuilder {
+    command: SubscriptionCommand,
+    case_id: Option<u64>,
+    person: Option<PersonRef>,
+    event_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum SubscriptionRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("A person can only be given to subscribe and unsubscribe, not {0}")]
+    PersonNotSupported(SubscriptionCommand),
+    #[error("An event id can only be given to view, not {0}")]
+    EventIdNotSupported(SubscriptionCommand),
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl SubscriptionRequestBuilder {
+    pub fn new(command: SubscriptionCommand) -> Self {
+        Self {
+            command,
+            case_id: None,
+            person: None,
+            event_id: None,
+            api: None,
+        }
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    /// Subscribes or unsubscribes someone else than the current user,
+    /// which requires administrator rights. Names are resolved when sending.
+    pub fn person(mut self, person: impl Into<PersonRef>) -> Self {
+        self.person = Some(person.into());
+        self
+    }
+    /// Last event seen when marking a case as read.
+    pub fn event_id(mut self, event_id: u64) -> Self {
+        self.event_id = Some(event_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<SubscriptionRequest, SubscriptionRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(SubscriptionRequestBuilderError::CaseIdNotSpecified)?;
+        let subscription = matches!(
+            self.command,
+            SubscriptionCommand::Subscribe | SubscriptionCommand::Unsubscribe
+        );
+        if self.person.is_some() && !subscription {
+            return Err(SubscriptionRequestBuilderError::PersonNotSupported(
+                self.command,
+            ));
+        }
+        if self.event_id.is_some() && self.command != SubscriptionCommand::View {
+            return Err(SubscriptionRequestBuilderError::EventIdNotSupported(
+                self.command,
+            ));
+        }
+        let api = self
+            .api
+            .ok_or(SubscriptionRequestBuilderError::ApiNotSpecified)?;
+        let (person_id, person) = match self.person {
+            Some(PersonRef::Id(id)) => (Some(id), None),
+            person => (None, person),
+        };
+        Ok(SubscriptionRequest {
+            command: self.command,
+            case_id,
+            person_id,
+            person,
+            event_id: self.event_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl SubscriptionRequest {
+    pub fn builder(command: SubscriptionCommand) -> SubscriptionRequestBuilder {
+        SubscriptionRequestBuilder::new(command)
+    }
+    pub async fn send(&self) -> Result<(), ResponseError> {
+        let mut request = self.clone();
+        if let Some(person) = request.person.take() {
+            request.person_id = Some(person.resolve(&self.api).await?);
+        }
+        let command = format!("api/{}", self.command);
+        send_command(&self.api, &command, &request).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_api;
+
+    #[test]
+    fn test_subscription_request_serialization() {
+        let request = test_api()
+            .subscribe()
+            .case_id(42)
+            .person(7)
+            .build()
+            .unwrap();
+        assert_eq!(request.command.to_string(), "subscribe");
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({"ixBug": 42, "ixPerson": 7, "token": "secret"})
+        );
+        let request = test_api().view().case_id(42).event_id(310).build().unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({"ixBug": 42, "ixBugEvent": 310, "token": "secret"})
+        );
+    }
+
+    #[test]
+    fn test_subscription_request_rejects_unsupported_arguments() {
+        let res = test_api().star().case_id(42).person(7).build();
+        assert!(matches!(
+            res,
+            Err(SubscriptionRequestBuilderError::PersonNotSupported(
+                SubscriptionCommand::Star
+            ))
+        ));
+        let res = test_api().unsubscribe().case_id(42).event_id(310).build();
+        assert!(matches!(
+            res,
+            Err(SubscriptionRequestBuilderError::EventIdNotSupported(
+                SubscriptionCommand::Unsubscribe
+            ))
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{list_intervals::Interval, send_command, FogbugzApi, ResponseError};
+
+/// Starts working on a case, stopping work on any other case.
+#[derive(Debug, Serialize)]
+pub struct StartWorkRequest {
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct StartWorkRequestBuilder {
+    case_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum StartWorkRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl StartWorkRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<StartWorkRequest, StartWorkRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(StartWorkRequestBuilderError::CaseIdNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(StartWorkRequestBuilderError::ApiNotSpecified)?;
+        Ok(StartWorkRequest {
+            case_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl StartWorkRequest {
+    pub fn builder() -> StartWorkRequestBuilder {
+        StartWorkRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<(), ResponseError> {
+        send_command(&self.api, "api/startWork", self).await?;
+        Ok(())
+    }
+}
+
+/// Stops working on whatever case the current user is working on.
+#[derive(Debug, Serialize)]
+pub struct StopWorkRequest {
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct StopWorkRequestBuilder {
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum StopWorkRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl StopWorkRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<StopWorkRequest, StopWorkRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(StopWorkRequestBuilderError::ApiNotSpecified)?;
+        Ok(StopWorkRequest {
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl StopWorkRequest {
+    pub fn builder() -> StopWorkRequestBuilder {
+        StopWorkRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<(), ResponseError> {
+        send_command(&self.api, "api/stopWork", self).await?;
+        Ok(())
+    }
+}
+
+/// Logs a finished interval of work on a case for the current user.
+#[derive(Debug, Serialize)]
+pub struct NewIntervalRequest {
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    #[serde(rename = "dtStart")]
+    start: DateTime<Utc>,
+    #[serde(rename = "dtEnd")]
+    end: DateTime<Utc>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct NewIntervalRequestBuilder {
+    case_id: Option<u64>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum NewIntervalRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Start is not specified")]
+    StartNotSpecified,
+    #[error("End is not specified")]
+    EndNotSpecified,
+    #[error("End {end} is not after start {start}")]
+    EndBeforeStart {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl NewIntervalRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start);
+        self
+    }
+    pub fn end(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<NewIntervalRequest, NewIntervalRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(NewIntervalRequestBuilderError::CaseIdNotSpecified)?;
+        let start = self
+            .start
+            .ok_or(NewIntervalRequestBuilderError::StartNotSpecified)?;
+        let end = self
+            .end
+            .ok_or(NewIntervalRequestBuilderError::EndNotSpecified)?;
+        if end <= start {
+            return Err(NewIntervalRequestBuilderError::EndBeforeStart { start, end });
+        }
+        let api = self
+            .api
+            .ok_or(NewIntervalRequestBuilderError::ApiNotSpecified)?;
+        Ok(NewIntervalRequest {
+            case_id,
+            start,
+            end,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl NewIntervalRequest {
+    pub fn builder() -> NewIntervalRequestBuilder {
+        NewIntervalRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Interval, ResponseError> {
+        let mut json = send_command(&self.api, "api/newInterval", self).await?;
+        let interval = serde_json::from_value::<Interval>(json["data"]["interval"].take())?;
+        Ok(interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::test_api;
+
+    #[test]
+    fn test_new_interval_requires_end_after_start() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap();
+        let res = test_api()
+            .new_interval()
+            .case_id(42)
+            .start(start)
+            .end(start)
+            .build();
+        assert!(matches!(
+            res,
+            Err(NewIntervalRequestBuilderError::EndBeforeStart { .. })
+        ));
+
+        let request = test_api()
+            .new_interval()
+            .case_id(42)
+            .start(start)
+            .end(start + chrono::Duration::minutes(90))
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "ixBug": 42,
+                "dtStart": "2024-01-08T09:00:00Z",
+                "dtEnd": "2024-01-08T10:30:00Z",
+                "token": "secret",
+            })
+        );
+    }
+}
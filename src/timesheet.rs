@@ -0,0 +1,435 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+use thiserror::Error;
+
+use crate::{
+    enums::Column,
+    export::{CsvExport, CsvExportError},
+    list_intervals::{Interval, ListIntervalsRequest, ListIntervalsRequestBuilderError},
+    refs::PersonRef,
+    search::{self, Case, DEFAULT_PAGE_SIZE},
+    FogbugzApi, ResponseError,
+};
+
+/// Length of the periods a [`TimesheetReport`] is split into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    #[default]
+    Day,
+    /// ISO week, starting on Monday.
+    Week,
+}
+
+impl Period {
+    /// First day of the period containing `date`.
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        }
+    }
+    fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => start + Duration::days(1),
+            Period::Week => start + Duration::weeks(1),
+        }
+    }
+}
+
+/// Time one person spent on one case during one period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimesheetRow {
+    pub person_id: u64,
+    pub case_id: u64,
+    pub title: String,
+    pub project_id: Option<u64>,
+    pub project: Option<String>,
+    /// First day of the period.
+    pub period: NaiveDate,
+    pub duration: Duration,
+    /// Whether an interval that is still open contributed to the row.
+    pub includes_open: bool,
+}
+
+impl TimesheetRow {
+    pub fn hours(&self) -> f64 {
+        hours(self.duration)
+    }
+}
+
+/// Logged time grouped by person, case, project and period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimesheetReport {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub period: Period,
+    /// Sorted by period, person and case.
+    pub rows: Vec<TimesheetRow>,
+}
+
+impl TimesheetReport {
+    /// Groups `intervals` clipped to `start..end`. Open intervals count until
+    /// `now`, deleted ones are skipped. Periods follow the calendar of
+    /// `offset`. `projects` maps case ids to their project id and name.
+    pub fn aggregate(
+        intervals: &[Interval],
+        projects: &HashMap<u64, (Option<u64>, Option<String>)>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+        period: Period,
+        offset: FixedOffset,
+    ) -> Self {
+        let mut rows: BTreeMap<(NaiveDate, u64, u64), TimesheetRow> = BTreeMap::new();
+        for interval in intervals.iter().filter(|interval| !interval.is_deleted) {
+            let from = interval.start.max(start);
+            let to = interval.end.unwrap_or(now).min(end);
+            let mut cursor = from;
+            while cursor < to {
+                let period_start = period.start_of(cursor.with_timezone(&offset).date_naive());
+                let boundary = local_midnight(period.next(period_start), offset).min(to);
+                let (project_id, project) =
+                    projects.get(&interval.case_id).cloned().unwrap_or_default();
+                let row = rows
+                    .entry((period_start, interval.person_id, interval.case_id))
+                    .or_insert_with(|| TimesheetRow {
+                        person_id: interval.person_id,
+                        case_id: interval.case_id,
+                        title: interval.title.clone(),
+                        project_id,
+                        project,
+                        period: period_start,
+                        duration: Duration::zero(),
+                        includes_open: false,
+                    });
+                row.duration += boundary - cursor;
+                row.includes_open |= interval.is_open();
+                cursor = boundary;
+            }
+        }
+        TimesheetReport {
+            start,
+            end,
+            period,
+            rows: rows.into_values().collect(),
+        }
+    }
+    pub fn total(&self) -> Duration {
+        self.rows.iter().map(|row| row.duration).sum()
+    }
+    pub fn by_person(&self) -> BTreeMap<u64, Duration> {
+        self.sum_by(|row| row.person_id)
+    }
+    pub fn by_case(&self) -> BTreeMap<u64, Duration> {
+        self.sum_by(|row| row.case_id)
+    }
+    /// Cases without a known project are grouped under `None`.
+    pub fn by_project(&self) -> BTreeMap<Option<u64>, Duration> {
+        self.sum_by(|row| row.project_id)
+    }
+    pub fn by_period(&self) -> BTreeMap<NaiveDate, Duration> {
+        self.sum_by(|row| row.period)
+    }
+    fn sum_by<K: Ord>(&self, key: impl Fn(&TimesheetRow) -> K) -> BTreeMap<K, Duration> {
+        let mut sums = BTreeMap::new();
+        for row in &self.rows {
+            *sums.entry(key(row)).or_insert_with(Duration::zero) += row.duration;
+        }
+        sums
+    }
+}
+
+impl CsvExport for TimesheetReport {
+    fn export_csv(&self) -> Result<String, CsvExportError> {
+        let mut csv = String::from("period,person_id,case_id,title,project_id,project,hours\n");
+        for row in &self.rows {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{:.2}\n",
+                row.period,
+                row.person_id,
+                row.case_id,
+                csv_field(&row.title),
+                row.project_id.map(|id| id.to_string()).unwrap_or_default(),
+                csv_field(row.project.as_deref().unwrap_or_default()),
+                row.hours(),
+            ));
+        }
+        Ok(csv)
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn hours(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+fn local_midnight(date: NaiveDate, offset: FixedOffset) -> DateTime<Utc> {
+    offset
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+/// Fetches the intervals of a date range and aggregates them into a
+/// [`TimesheetReport`].
+#[derive(Debug)]
+pub struct TimesheetRequest {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    /// One `listIntervals` request per reported person.
+    intervals: Vec<ListIntervalsRequest>,
+    period: Period,
+    offset: FixedOffset,
+    now: Option<DateTime<Utc>>,
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct TimesheetRequestBuilder {
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    people: Vec<PersonRef>,
+    period: Period,
+    offset: Option<FixedOffset>,
+    now: Option<DateTime<Utc>>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum TimesheetRequestBuilderError {
+    #[error("Start is not specified")]
+    StartNotSpecified,
+    #[error("End is not specified")]
+    EndNotSpecified,
+    #[error("End {end} is not after start {start}")]
+    EndBeforeStart {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+    #[error(transparent)]
+    ListIntervals(#[from] ListIntervalsRequestBuilderError),
+}
+
+impl TimesheetRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start);
+        self
+    }
+    pub fn end(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end);
+        self
+    }
+    /// Adds a person to the report, without any only the current user is
+    /// reported.
+    pub fn person(mut self, person: impl Into<PersonRef>) -> Self {
+        self.people.push(person.into());
+        self
+    }
+    pub fn period(mut self, period: Period) -> Self {
+        self.period = period;
+        self
+    }
+    /// Offset of the calendar days and weeks, UTC by default.
+    pub fn offset(mut self, offset: FixedOffset) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+    /// End of the intervals that are still open, the current time by default.
+    pub fn now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<TimesheetRequest, TimesheetRequestBuilderError> {
+        let start = self
+            .start
+            .ok_or(TimesheetRequestBuilderError::StartNotSpecified)?;
+        let end = self
+            .end
+            .ok_or(TimesheetRequestBuilderError::EndNotSpecified)?;
+        if end <= start {
+            return Err(TimesheetRequestBuilderError::EndBeforeStart { start, end });
+        }
+        let api = self
+            .api
+            .ok_or(TimesheetRequestBuilderError::ApiNotSpecified)?;
+        let people = if self.people.is_empty() {
+            vec![None]
+        } else {
+            self.people.into_iter().map(Some).collect()
+        };
+        let intervals = people
+            .into_iter()
+            .map(|person| {
+                let builder = api
+                    .list_intervals()
+                    .start_date(start.naive_utc())
+                    .end_date(end.naive_utc());
+                match person {
+                    Some(person) => builder.person(person),
+                    None => builder,
+                }
+                .build()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(TimesheetRequest {
+            start,
+            end,
+            intervals,
+            period: self.period,
+            offset: self
+                .offset
+                .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap()),
+            now: self.now,
+            api,
+        })
+    }
+}
+
+impl TimesheetRequest {
+    pub fn builder() -> TimesheetRequestBuilder {
+        TimesheetRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<TimesheetReport, ResponseError> {
+        let now = self.now.unwrap_or_else(Utc::now);
+        let mut intervals = Vec::new();
+        for request in &self.intervals {
+            intervals.extend(request.send().await?);
+        }
+        let projects = self.projects(&intervals).await?;
+        Ok(TimesheetReport::aggregate(
+            &intervals,
+            &projects,
+            self.start,
+            self.end,
+            now,
+            self.period,
+            self.offset,
+        ))
+    }
+    /// Project of every case with an interval.
+    async fn projects(
+        &self,
+        intervals: &[Interval],
+    ) -> Result<HashMap<u64, (Option<u64>, Option<String>)>, ResponseError> {
+        let mut case_ids: Vec<u64> = intervals.iter().map(|interval| interval.case_id).collect();
+        case_ids.sort_unstable();
+        case_ids.dedup();
+        if case_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let cols = [Column::CaseId, Column::ProjectId, Column::Project];
+        let mut projects = HashMap::new();
+        for batch in case_ids.chunks(DEFAULT_PAGE_SIZE) {
+            for case in search::fetch_cases(&self.api, batch, &cols).await? {
+                let case: Case = serde_json::from_value(case)?;
+                projects.insert(case.case_id, (case.project_id, case.project));
+            }
+        }
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn interval(id: u64, case_id: u64, start: &str, end: Option<&str>) -> Interval {
+        Interval {
+            id,
+            case_id,
+            person_id: 7,
+            start: date(start),
+            end: end.map(date),
+            title: format!("Case {}", case_id),
+            is_deleted: false,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_clips_and_splits_days() {
+        let intervals = vec![
+            // Starts before the range and crosses midnight.
+            interval(1, 42, "2023-12-31T22:00:00Z", Some("2024-01-01T02:00:00Z")),
+            interval(2, 42, "2024-01-01T22:00:00Z", Some("2024-01-02T01:00:00Z")),
+            // Still open, counted until `now`.
+            interval(3, 43, "2024-01-02T09:00:00Z", None),
+        ];
+        let projects = HashMap::from([(42, (Some(2), Some("Website".to_string())))]);
+        let report = TimesheetReport::aggregate(
+            &intervals,
+            &projects,
+            date("2024-01-01T00:00:00Z"),
+            date("2024-02-01T00:00:00Z"),
+            date("2024-01-02T10:30:00Z"),
+            Period::Day,
+            FixedOffset::east_opt(0).unwrap(),
+        );
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        assert_eq!(
+            report.by_period(),
+            BTreeMap::from([
+                (day("2024-01-01"), Duration::hours(4)),
+                (day("2024-01-02"), Duration::minutes(150)),
+            ])
+        );
+        assert_eq!(report.by_case()[&43], Duration::minutes(90));
+        assert_eq!(report.by_project()[&Some(2)], Duration::hours(5));
+        assert_eq!(report.by_project()[&None], Duration::minutes(90));
+        assert_eq!(report.by_person()[&7], report.total());
+        assert!(report.rows.iter().any(|row| row.includes_open));
+    }
+
+    #[test]
+    fn test_aggregate_by_iso_week_with_offset() {
+        // Sunday 21:30-22:30 UTC is 23:30-00:30 in UTC+2, so half of the
+        // interval falls into the next week.
+        let intervals = vec![interval(
+            1,
+            42,
+            "2024-01-07T21:30:00Z",
+            Some("2024-01-07T22:30:00Z"),
+        )];
+        let report = TimesheetReport::aggregate(
+            &intervals,
+            &HashMap::new(),
+            date("2024-01-01T00:00:00Z"),
+            date("2024-02-01T00:00:00Z"),
+            date("2024-02-01T00:00:00Z"),
+            Period::Week,
+            FixedOffset::east_opt(2 * 3600).unwrap(),
+        );
+        let weeks: Vec<_> = report.by_period().into_iter().collect();
+        assert_eq!(
+            weeks,
+            vec![
+                ("2024-01-01".parse().unwrap(), Duration::minutes(30)),
+                ("2024-01-08".parse().unwrap(), Duration::minutes(30)),
+            ]
+        );
+        assert_eq!(
+            report.export_csv().unwrap().lines().nth(1),
+            Some("2024-01-01,7,42,Case 42,,,0.50")
+        );
+    }
+}
@@ -0,0 +1,521 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{send_command, FogbugzApi, ResponseError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wiki {
+    #[serde(rename = "ixWiki")]
+    pub id: u64,
+    #[serde(rename = "sWiki")]
+    pub name: String,
+    #[serde(rename = "sTagLineHTML", default)]
+    pub tagline: Option<String>,
+    #[serde(rename = "ixWikiPageRoot", default)]
+    pub root_article_id: Option<u64>,
+    #[serde(rename = "ixTemplate", default)]
+    pub template_id: Option<u64>,
+}
+
+/// Article as listed by `listArticles`, without its body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleSummary {
+    #[serde(rename = "ixWikiPage")]
+    pub id: u64,
+    #[serde(rename = "sHeadline")]
+    pub headline: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Article {
+    #[serde(rename = "ixWikiPage")]
+    pub id: u64,
+    #[serde(rename = "ixWiki", default)]
+    pub wiki_id: Option<u64>,
+    #[serde(rename = "sHeadline")]
+    pub headline: String,
+    /// HTML body of the article.
+    #[serde(rename = "sBody", default)]
+    pub body: String,
+    #[serde(rename = "nRevision", default)]
+    pub revision: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    #[serde(rename = "nRevision")]
+    pub revision: u64,
+    #[serde(rename = "ixPerson")]
+    pub person_id: u64,
+    #[serde(rename = "sComment", default)]
+    pub comment: Option<String>,
+    #[serde(rename = "dt")]
+    pub date: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListWikisRequest {
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListWikisRequestBuilder {
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListWikisRequestBuilderError {
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListWikisRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListWikisRequest, ListWikisRequestBuilderError> {
+        let api = self
+            .api
+            .ok_or(ListWikisRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListWikisRequest {
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListWikisRequest {
+    pub fn builder() -> ListWikisRequestBuilder {
+        ListWikisRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Wiki>, ResponseError> {
+        let mut json = send_command(&self.api, "api/listWikis", self).await?;
+        let wikis = serde_json::from_value(json["data"]["wikis"].take())?;
+        Ok(wikis)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListArticlesRequest {
+    #[serde(rename = "ixWiki")]
+    wiki_id: u64,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListArticlesRequestBuilder {
+    wiki_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListArticlesRequestBuilderError {
+    #[error("Wiki id is not specified")]
+    WikiIdNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListArticlesRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn wiki_id(mut self, wiki_id: u64) -> Self {
+        self.wiki_id = Some(wiki_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListArticlesRequest, ListArticlesRequestBuilderError> {
+        let wiki_id = self
+            .wiki_id
+            .ok_or(ListArticlesRequestBuilderError::WikiIdNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(ListArticlesRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListArticlesRequest {
+            wiki_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListArticlesRequest {
+    pub fn builder() -> ListArticlesRequestBuilder {
+        ListArticlesRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<ArticleSummary>, ResponseError> {
+        let mut json = send_command(&self.api, "api/listArticles", self).await?;
+        let articles = serde_json::from_value(json["data"]["articles"].take())?;
+        Ok(articles)
+    }
+}
+
+/// Reads an article, at its latest revision unless one is given.
+#[derive(Debug, Serialize)]
+pub struct ViewArticleRequest {
+    #[serde(rename = "ixWikiPage")]
+    article_id: u64,
+    #[serde(rename = "nRevision", skip_serializing_if = "Option::is_none")]
+    revision: Option<u64>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ViewArticleRequestBuilder {
+    article_id: Option<u64>,
+    revision: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ViewArticleRequestBuilderError {
+    #[error("Article id is not specified")]
+    ArticleIdNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ViewArticleRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn article_id(mut self, article_id: u64) -> Self {
+        self.article_id = Some(article_id);
+        self
+    }
+    pub fn revision(mut self, revision: u64) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ViewArticleRequest, ViewArticleRequestBuilderError> {
+        let article_id = self
+            .article_id
+            .ok_or(ViewArticleRequestBuilderError::ArticleIdNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(ViewArticleRequestBuilderError::ApiNotSpecified)?;
+        Ok(ViewArticleRequest {
+            article_id,
+            revision: self.revision,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ViewArticleRequest {
+    pub fn builder() -> ViewArticleRequestBuilder {
+        ViewArticleRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Article, ResponseError> {
+        let mut json = send_command(&self.api, "api/viewArticle", self).await?;
+        let article = serde_json::from_value(json["data"]["wikipage"].take())?;
+        Ok(article)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NewArticleRequest {
+    #[serde(rename = "ixWiki")]
+    wiki_id: u64,
+    #[serde(rename = "sHeadline")]
+    headline: String,
+    #[serde(rename = "sBody")]
+    body: String,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct NewArticleRequestBuilder {
+    wiki_id: Option<u64>,
+    headline: Option<String>,
+    body: Option<String>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum NewArticleRequestBuilderError {
+    #[error("Wiki id is not specified")]
+    WikiIdNotSpecified,
+    #[error("Headline is not specified")]
+    HeadlineNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl NewArticleRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn wiki_id(mut self, wiki_id: u64) -> Self {
+        self.wiki_id = Some(wiki_id);
+        self
+    }
+    pub fn headline(mut self, headline: impl AsRef<str>) -> Self {
+        self.headline = Some(headline.as_ref().to_string());
+        self
+    }
+    /// HTML body of the article.
+    pub fn body(mut self, body: impl AsRef<str>) -> Self {
+        self.body = Some(body.as_ref().to_string());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<NewArticleRequest, NewArticleRequestBuilderError> {
+        let wiki_id = self
+            .wiki_id
+            .ok_or(NewArticleRequestBuilderError::WikiIdNotSpecified)?;
+        let headline = self
+            .headline
+            .ok_or(NewArticleRequestBuilderError::HeadlineNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(NewArticleRequestBuilderError::ApiNotSpecified)?;
+        Ok(NewArticleRequest {
+            wiki_id,
+            headline,
+            body: self.body.unwrap_or_default(),
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl NewArticleRequest {
+    pub fn builder() -> NewArticleRequestBuilder {
+        NewArticleRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Article, ResponseError> {
+        let mut json = send_command(&self.api, "api/newArticle", self).await?;
+        let article = serde_json::from_value(json["data"]["wikipage"].take())?;
+        Ok(article)
+    }
+}
+
+/// Saves a new revision of an article, fields left unset keep their value.
+#[derive(Debug, Serialize)]
+pub struct EditArticleRequest {
+    #[serde(rename = "ixWikiPage")]
+    article_id: u64,
+    #[serde(rename = "sHeadline", skip_serializing_if = "Option::is_none")]
+    headline: Option<String>,
+    #[serde(rename = "sBody", skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    #[serde(rename = "sComment", skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct EditArticleRequestBuilder {
+    article_id: Option<u64>,
+    headline: Option<String>,
+    body: Option<String>,
+    comment: Option<String>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum EditArticleRequestBuilderError {
+    #[error("Article id is not specified")]
+    ArticleIdNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl EditArticleRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn article_id(mut self, article_id: u64) -> Self {
+        self.article_id = Some(article_id);
+        self
+    }
+    pub fn headline(mut self, headline: impl AsRef<str>) -> Self {
+        self.headline = Some(headline.as_ref().to_string());
+        self
+    }
+    /// HTML body of the article.
+    pub fn body(mut self, body: impl AsRef<str>) -> Self {
+        self.body = Some(body.as_ref().to_string());
+        self
+    }
+    /// Comment shown in the revision history.
+    pub fn comment(mut self, comment: impl AsRef<str>) -> Self {
+        self.comment = Some(comment.as_ref().to_string());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<EditArticleRequest, EditArticleRequestBuilderError> {
+        let article_id = self
+            .article_id
+            .ok_or(EditArticleRequestBuilderError::ArticleIdNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(EditArticleRequestBuilderError::ApiNotSpecified)?;
+        Ok(EditArticleRequest {
+            article_id,
+            headline: self.headline,
+            body: self.body,
+            comment: self.comment,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl EditArticleRequest {
+    pub fn builder() -> EditArticleRequestBuilder {
+        EditArticleRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Article, ResponseError> {
+        let mut json = send_command(&self.api, "api/editArticle", self).await?;
+        let article = serde_json::from_value(json["data"]["wikipage"].take())?;
+        Ok(article)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListRevisionsRequest {
+    #[serde(rename = "ixWikiPage")]
+    article_id: u64,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ListRevisionsRequestBuilder {
+    article_id: Option<u64>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListRevisionsRequestBuilderError {
+    #[error("Article id is not specified")]
+    ArticleIdNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ListRevisionsRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn article_id(mut self, article_id: u64) -> Self {
+        self.article_id = Some(article_id);
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ListRevisionsRequest, ListRevisionsRequestBuilderError> {
+        let article_id = self
+            .article_id
+            .ok_or(ListRevisionsRequestBuilderError::ArticleIdNotSpecified)?;
+        let api = self
+            .api
+            .ok_or(ListRevisionsRequestBuilderError::ApiNotSpecified)?;
+        Ok(ListRevisionsRequest {
+            article_id,
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ListRevisionsRequest {
+    pub fn builder() -> ListRevisionsRequestBuilder {
+        ListRevisionsRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<Vec<Revision>, ResponseError> {
+        let mut json = send_command(&self.api, "api/listRevisions", self).await?;
+        let revisions = serde_json::from_value(json["data"]["revisions"].take())?;
+        Ok(revisions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_api;
+
+    #[test]
+    fn test_article_request_serialization() {
+        let res = test_api().new_article().wiki_id(1).build();
+        assert!(matches!(
+            res,
+            Err(NewArticleRequestBuilderError::HeadlineNotSpecified)
+        ));
+        let request = test_api()
+            .edit_article()
+            .article_id(12)
+            .body("<p>Restart the worker.</p>")
+            .comment("Synced from runbooks")
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "ixWikiPage": 12,
+                "sBody": "<p>Restart the worker.</p>",
+                "sComment": "Synced from runbooks",
+                "token": "secret",
+            })
+        );
+    }
+
+    #[test]
+    fn test_deserialize_wiki_responses() {
+        let json = serde_json::json!({
+            "wikis": [{"ixWiki": 1, "sWiki": "Runbooks", "sTagLineHTML": "", "ixWikiPageRoot": 3}],
+            "wikipage": {
+                "ixWikiPage": 12,
+                "ixWiki": 1,
+                "sHeadline": "Worker restarts",
+                "sBody": "<p>Restart the worker.</p>",
+                "nRevision": 4
+            },
+            "revisions": [
+                {"nRevision": 4, "ixPerson": 7, "sComment": "Synced", "dt": "2024-01-08T09:00:00Z"}
+            ]
+        });
+        let wikis = serde_json::from_value::<Vec<Wiki>>(json["wikis"].clone()).unwrap();
+        assert_eq!(wikis[0].root_article_id, Some(3));
+        let article = serde_json::from_value::<Article>(json["wikipage"].clone()).unwrap();
+        assert_eq!(article.revision, 4);
+        assert_eq!(article.wiki_id, Some(1));
+        let revisions = serde_json::from_value::<Vec<Revision>>(json["revisions"].clone()).unwrap();
+        assert_eq!(revisions[0].person_id, 7);
+    }
+}
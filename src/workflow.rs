@@ -0,0 +1,551 @@
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    attachments::send_case_command,
+    case_details::{self, CaseDetails},
+    enums::{Column, StatusRegistry},
+    refs::{unique, PersonRef, ResolveError},
+    search, FogbugzApi, ResponseError,
+};
+
+/// Resolved status applied by the `resolve` command.
+///
+/// Status ids differ between FogBugz instances and each category has its own
+/// resolved statuses, so names are looked up among the statuses of the case's
+/// category when sending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolutionStatus {
+    Id(u64),
+    Name(String),
+    /// Duplicate status of the case's category, the original case is set
+    /// with [`ResolveRequestBuilder::original_case_id`].
+    Duplicate,
+}
+
+impl ResolutionStatus {
+    /// Id of the resolved status of `category_id` this stands for.
+    fn resolve(&self, registry: &StatusRegistry, category_id: u64) -> Result<u64, ResponseError> {
+        let candidates = registry
+            .iter()
+            .filter(|info| info.category_id == category_id && info.is_resolved && !info.is_deleted);
+        match self {
+            ResolutionStatus::Id(id) => Ok(*id),
+            ResolutionStatus::Name(name) => unique(
+                "status",
+                name,
+                candidates.filter(|info| info.name.eq_ignore_ascii_case(name)),
+            ),
+            ResolutionStatus::Duplicate => unique(
+                "status",
+                "Duplicate",
+                candidates.filter(|info| info.is_duplicate),
+            ),
+        }
+    }
+}
+
+impl From<u64> for ResolutionStatus {
+    fn from(id: u64) -> Self {
+        ResolutionStatus::Id(id)
+    }
+}
+
+impl From<&str> for ResolutionStatus {
+    fn from(name: &str) -> Self {
+        ResolutionStatus::Name(name.to_string())
+    }
+}
+
+impl From<String> for ResolutionStatus {
+    fn from(name: String) -> Self {
+        ResolutionStatus::Name(name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssignRequest {
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    #[serde(rename = "ixPersonAssignedTo", skip_serializing_if = "Option::is_none")]
+    assigned_to_id: Option<u64>,
+    #[serde(skip)]
+    assigned_to: Option<PersonRef>,
+    #[serde(rename = "sEvent", skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cols: Option<Vec<String>>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct AssignRequestBuilder {
+    case_id: Option<u64>,
+    assigned_to: Option<PersonRef>,
+    event: Option<String>,
+    cols: Option<Vec<String>>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum AssignRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Invalid case id: {0}")]
+    InvalidCaseId(u64),
+    #[error("Assignee is not specified")]
+    AssigneeNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl AssignRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    pub fn assigned_to_id(mut self, person_id: u64) -> Self {
+        self.assigned_to = Some(PersonRef::Id(person_id));
+        self
+    }
+    /// Assignee by id, full name or email, names are resolved when sending.
+    pub fn assigned_to(mut self, person: impl Into<PersonRef>) -> Self {
+        self.assigned_to = Some(person.into());
+        self
+    }
+    pub fn event(mut self, event: impl AsRef<str>) -> Self {
+        self.event = Some(event.as_ref().to_string());
+        self
+    }
+    /// Extra columns to return; the columns [`CaseDetails`] needs are always
+    /// requested as well.
+    pub fn cols(mut self, cols: Vec<Column>) -> Self {
+        self.cols = Some(cols.into_iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<AssignRequest, AssignRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(AssignRequestBuilderError::CaseIdNotSpecified)?;
+        if case_id == 0 {
+            return Err(AssignRequestBuilderError::InvalidCaseId(case_id));
+        }
+        let (assigned_to_id, assigned_to) = match self.assigned_to {
+            Some(PersonRef::Id(id)) => (Some(id), None),
+            Some(person) => (None, Some(person)),
+            None => return Err(AssignRequestBuilderError::AssigneeNotSpecified),
+        };
+        let api = self.api.ok_or(AssignRequestBuilderError::ApiNotSpecified)?;
+        Ok(AssignRequest {
+            case_id,
+            assigned_to_id,
+            assigned_to,
+            event: self.event,
+            cols: Some(case_details::with_default_cols(
+                self.cols.unwrap_or_default(),
+            )),
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl AssignRequest {
+    pub fn builder() -> AssignRequestBuilder {
+        AssignRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<CaseDetails, ResponseError> {
+        let mut request = self.clone();
+        if let Some(person) = request.assigned_to.take() {
+            request.assigned_to_id = Some(person.resolve(&self.api).await?);
+        }
+        send_case_command(&self.api, "api/assign", &request, &[]).await
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolveRequest {
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    #[serde(rename = "ixStatus", skip_serializing_if = "Option::is_none")]
+    status_id: Option<u64>,
+    #[serde(skip)]
+    status: Option<ResolutionStatus>,
+    #[serde(rename = "ixBugOriginal", skip_serializing_if = "Option::is_none")]
+    original_case_id: Option<u64>,
+    #[serde(rename = "ixPersonAssignedTo", skip_serializing_if = "Option::is_none")]
+    assigned_to_id: Option<u64>,
+    #[serde(skip)]
+    assigned_to: Option<PersonRef>,
+    #[serde(rename = "sEvent", skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cols: Option<Vec<String>>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Default)]
+pub struct ResolveRequestBuilder {
+    case_id: Option<u64>,
+    status: Option<ResolutionStatus>,
+    original_case_id: Option<u64>,
+    assigned_to: Option<PersonRef>,
+    event: Option<String>,
+    cols: Option<Vec<String>>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum ResolveRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Invalid case id: {0}")]
+    InvalidCaseId(u64),
+    #[error("Resolution status is not specified")]
+    StatusNotSpecified,
+    #[error("Original case is not specified for a duplicate")]
+    OriginalCaseNotSpecified,
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl ResolveRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    /// Resolved status by `ixStatus` or name, names are resolved when sending.
+    pub fn status(mut self, status: impl Into<ResolutionStatus>) -> Self {
+        self.status = Some(status.into());
+        self
+    }
+    /// Case this one duplicates, required by [`ResolutionStatus::Duplicate`].
+    pub fn original_case_id(mut self, case_id: u64) -> Self {
+        self.original_case_id = Some(case_id);
+        self
+    }
+    /// Person the resolved case goes to, FogBugz defaults to whoever opened it.
+    pub fn assigned_to_id(mut self, person_id: u64) -> Self {
+        self.assigned_to = Some(PersonRef::Id(person_id));
+        self
+    }
+    /// Like [`Self::assigned_to_id`] but by id, full name or email.
+    pub fn assigned_to(mut self, person: impl Into<PersonRef>) -> Self {
+        self.assigned_to = Some(person.into());
+        self
+    }
+    pub fn event(mut self, event: impl AsRef<str>) -> Self {
+        self.event = Some(event.as_ref().to_string());
+        self
+    }
+    /// Extra columns to return; the columns [`CaseDetails`] needs are always
+    /// requested as well.
+    pub fn cols(mut self, cols: Vec<Column>) -> Self {
+        self.cols = Some(cols.into_iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<ResolveRequest, ResolveRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(ResolveRequestBuilderError::CaseIdNotSpecified)?;
+        if case_id == 0 {
+            return Err(ResolveRequestBuilderError::InvalidCaseId(case_id));
+        }
+        let status = self
+            .status
+            .ok_or(ResolveRequestBuilderError::StatusNotSpecified)?;
+        if status == ResolutionStatus::Duplicate && self.original_case_id.is_none() {
+            return Err(ResolveRequestBuilderError::OriginalCaseNotSpecified);
+        }
+        let (status_id, status) = match status {
+            ResolutionStatus::Id(id) => (Some(id), None),
+            status => (None, Some(status)),
+        };
+        let api = self
+            .api
+            .ok_or(ResolveRequestBuilderError::ApiNotSpecified)?;
+        let (assigned_to_id, assigned_to) = match self.assigned_to {
+            Some(PersonRef::Id(id)) => (Some(id), None),
+            person => (None, person),
+        };
+        Ok(ResolveRequest {
+            case_id,
+            status_id,
+            status,
+            original_case_id: self.original_case_id,
+            assigned_to_id,
+            assigned_to,
+            event: self.event,
+            cols: Some(case_details::with_default_cols(
+                self.cols.unwrap_or_default(),
+            )),
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl ResolveRequest {
+    pub fn builder() -> ResolveRequestBuilder {
+        ResolveRequestBuilder::new()
+    }
+    pub async fn send(&self) -> Result<CaseDetails, ResponseError> {
+        let mut request = self.clone();
+        if let Some(status) = request.status.take() {
+            let category_id = self.category_id().await?;
+            let statuses = self.api.statuses().await?;
+            request.status_id = Some(status.resolve(&statuses, category_id)?);
+        }
+        if let Some(person) = request.assigned_to.take() {
+            request.assigned_to_id = Some(person.resolve(&self.api).await?);
+        }
+        send_case_command(&self.api, "api/resolve", &request, &[]).await
+    }
+    async fn category_id(&self) -> Result<u64, ResponseError> {
+        let cases = search::fetch_cases(
+            &self.api,
+            &[self.case_id],
+            &[Column::CaseId, Column::Category],
+        )
+        .await?;
+        cases
+            .first()
+            .and_then(|case| case["ixCategory"].as_u64())
+            .ok_or_else(|| {
+                ResolveError::NotFound {
+                    kind: "case",
+                    name: self.case_id.to_string(),
+                }
+                .into()
+            })
+    }
+}
+
+/// Request shared by the commands that only move a case to another state:
+/// `reactivate`, `reopen` and `close`.
+#[derive(Debug, Serialize)]
+pub struct TransitionRequest {
+    #[serde(skip)]
+    command: Transition,
+    #[serde(rename = "ixBug")]
+    case_id: u64,
+    #[serde(rename = "sEvent", skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cols: Option<Vec<String>>,
+    token: String,
+    #[serde(skip)]
+    api: FogbugzApi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+pub enum Transition {
+    /// Resolved case back to active.
+    #[strum(serialize = "reactivate")]
+    Reactivate,
+    /// Closed case back to active.
+    #[strum(serialize = "reopen")]
+    Reopen,
+    /// Resolved case to closed.
+    #[strum(serialize = "close")]
+    Close,
+}
+
+#[derive(Debug)]
+pub struct TransitionRequestBuilder {
+    command: Transition,
+    case_id: Option<u64>,
+    event: Option<String>,
+    cols: Option<Vec<String>>,
+    api: Option<FogbugzApi>,
+}
+
+#[derive(Debug, Error)]
+pub enum TransitionRequestBuilderError {
+    #[error("Case id is not specified")]
+    CaseIdNotSpecified,
+    #[error("Invalid case id: {0}")]
+    InvalidCaseId(u64),
+    #[error("Api is not specified")]
+    ApiNotSpecified,
+}
+
+impl TransitionRequestBuilder {
+    pub fn new(command: Transition) -> Self {
+        Self {
+            command,
+            case_id: None,
+            event: None,
+            cols: None,
+            api: None,
+        }
+    }
+    pub fn case_id(mut self, case_id: u64) -> Self {
+        self.case_id = Some(case_id);
+        self
+    }
+    pub fn event(mut self, event: impl AsRef<str>) -> Self {
+        self.event = Some(event.as_ref().to_string());
+        self
+    }
+    /// Extra columns to return; the columns [`CaseDetails`] needs are always
+    /// requested as well.
+    pub fn cols(mut self, cols: Vec<Column>) -> Self {
+        self.cols = Some(cols.into_iter().map(|s| s.to_string()).collect());
+        self
+    }
+    pub fn api(mut self, api: FogbugzApi) -> Self {
+        self.api = Some(api);
+        self
+    }
+    pub fn build(self) -> Result<TransitionRequest, TransitionRequestBuilderError> {
+        let case_id = self
+            .case_id
+            .ok_or(TransitionRequestBuilderError::CaseIdNotSpecified)?;
+        if case_id == 0 {
+            return Err(TransitionRequestBuilderError::InvalidCaseId(case_id));
+        }
+        let api = self
+            .api
+            .ok_or(TransitionRequestBuilderError::ApiNotSpecified)?;
+        Ok(TransitionRequest {
+            command: self.command,
+            case_id,
+            event: self.event,
+            cols: Some(case_details::with_default_cols(
+                self.cols.unwrap_or_default(),
+            )),
+            token: api.api_key.clone(),
+            api,
+        })
+    }
+}
+
+impl TransitionRequest {
+    pub fn builder(command: Transition) -> TransitionRequestBuilder {
+        TransitionRequestBuilder::new(command)
+    }
+    pub async fn send(&self) -> Result<CaseDetails, ResponseError> {
+        send_case_command(&self.api, &format!("api/{}", self.command), self, &[]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{metadata::StatusInfo, test_api};
+
+    #[test]
+    fn test_assign_request_requires_assignee() {
+        let res = test_api().assign().case_id(42).build();
+        assert!(matches!(
+            res,
+            Err(AssignRequestBuilderError::AssigneeNotSpecified)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_request_serialization() {
+        let res = test_api().resolve().case_id(42).build();
+        assert!(matches!(
+            res,
+            Err(ResolveRequestBuilderError::StatusNotSpecified)
+        ));
+        let request = test_api()
+            .resolve()
+            .case_id(42)
+            .status(28)
+            .event("Working as intended")
+            .cols(vec![Column::CaseId, Column::Tags])
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "ixBug": 42,
+                "ixStatus": 28,
+                "sEvent": "Working as intended",
+                "cols": [
+                    "ixBug", "sTitle", "events", "sProject", "sArea", "ixPriority",
+                    "ixStatus", "ixCategory", "fOpen", "ixBugParent", "ixBugChildren", "tags",
+                ],
+                "token": "secret",
+            })
+        );
+    }
+
+    #[test]
+    fn test_duplicate_resolve_requires_original_case() {
+        let res = test_api()
+            .resolve()
+            .case_id(42)
+            .status(ResolutionStatus::Duplicate)
+            .build();
+        assert!(matches!(
+            res,
+            Err(ResolveRequestBuilderError::OriginalCaseNotSpecified)
+        ));
+        let request = test_api()
+            .resolve()
+            .case_id(42)
+            .status(ResolutionStatus::Duplicate)
+            .original_case_id(7)
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["ixBugOriginal"], 7);
+        assert!(json.get("ixStatus").is_none());
+    }
+
+    #[test]
+    fn test_resolution_status_is_looked_up_in_the_case_category() {
+        let statuses: Vec<StatusInfo> =
+            serde_json::from_str(include_str!("../tests/fixtures/statuses.json")).unwrap();
+        let registry: StatusRegistry = statuses.into_iter().collect();
+        let status = ResolutionStatus::from("resolved (postponed)");
+        assert_eq!(status.resolve(&registry, 2).unwrap(), 32);
+        assert_eq!(status.resolve(&registry, 4).unwrap(), 31);
+        assert!(matches!(
+            status.resolve(&registry, 6),
+            Err(ResponseError::ResolveError(ResolveError::NotFound { .. }))
+        ));
+        assert_eq!(
+            ResolutionStatus::Duplicate.resolve(&registry, 7).unwrap(),
+            39
+        );
+        // Deleted statuses are not offered.
+        assert!(ResolutionStatus::from("Won't Review")
+            .resolve(&registry, 5)
+            .is_err());
+        assert_eq!(ResolutionStatus::Id(3).resolve(&registry, 5).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_transition_request_rejects_invalid_case_id() {
+        let res = test_api().close().case_id(0).build();
+        assert!(matches!(
+            res,
+            Err(TransitionRequestBuilderError::InvalidCaseId(0))
+        ));
+        let request = test_api().reopen().case_id(42).build().unwrap();
+        assert_eq!(request.command.to_string(), "reopen");
+    }
+}